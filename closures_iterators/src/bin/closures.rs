@@ -1,4 +1,10 @@
 #![allow(unused_mut)]
+// the closures around give_num() are the point of this file, so clippy is not asked to remove them
+#![allow(clippy::redundant_closure)]
+use std::time::Duration;
+
+use closures_iterators::retry::{Backoff, Fallback, Policy, ThreadSleeper};
+
 fn main(){
     // "|| give_num()" is a closure. The unwrap_or_else function, unwraps a Some(T) if
     // a Some(T) is given or computes a value from the closure i.e "|| give_num()"
@@ -89,6 +95,24 @@ fn main(){
     // let add = |x,y| x + y + z;
     // calculater(5, 10, add); panics as "add fn" captures a variable "z" in its definition
 
+    // ------ Many Fallbacks, src/retry.rs ---------
+    // unwrap_or_else above has one fallback, Fallback takes a list of closures and tries them in order
+    // a closure that gives None or an Err is tried again if its Policy allows, then the next one is tried
+    let mut lookups = 0;
+    let found = Fallback::<i32, String>::new()
+        .option("return_option", || return_option(15))
+        .result("slow lookup", || {
+            lookups += 1;
+            if lookups < 3 { Err(format!("not ready after {} tries", lookups)) } else { Ok(30) }
+        })
+        .retry(Policy::new(3, Backoff::Exponential { initial: Duration::from_millis(1), max: Duration::from_millis(10) }))
+        .value("give_num", || give_num())
+        .run(&mut ThreadSleeper);
+    match found {
+        Ok(found) => println!("{} from {} after {} attempts\n", found.value, found.producer, found.attempts.len()),
+        Err(error) => println!("{}\n", error),
+    }
+
 
 
 }
@@ -0,0 +1,5 @@
+// Only the closures that outgrew src/bin/closures.rs live in this library crate,
+// the rest of the examples stay in the binaries, eg: closures_iterators::retry::Fallback
+
+// Fallback: try producers (closures) in order, with retries and backoff, like unwrap_or_else for many
+pub mod retry;
@@ -0,0 +1,310 @@
+// Trying producers one after the other until one gives a value
+//
+// closures.rs does it for two with return_option(i).unwrap_or_else(|| give_num()),
+// Fallback does it for any number of them, and can try each one more than once:
+//
+//   let found = Fallback::new()
+//       .option("cache", || cache.get(key).cloned())
+//       .result("disk", || read_from_disk(key)).retry(Policy::new(3, Backoff::Fixed(Duration::from_millis(50))))
+//       .value("default", || Config::default())
+//       .run(&mut ThreadSleeper)?;
+//   println!("{} came from {}", found.value, found.producer);
+//
+// a producer fails by returning None or Err, and is tried again while it has attempts left,
+// sleeping for its backoff before each retry. When it has no attempts left the next one is tried
+// right away. Every attempt is recorded, with the error when there was one.
+// The sleeper is passed in, so tests can record the sleeps instead of waiting
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+// ------ Sleeping ------
+pub trait Sleeper {
+    fn sleep(&mut self, duration: Duration);
+}
+
+// really sleeps, with std::thread::sleep
+pub struct ThreadSleeper;
+
+impl Sleeper for ThreadSleeper {
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+// any closure can be a sleeper, eg: |duration| slept.push(duration)
+impl<F: FnMut(Duration)> Sleeper for F {
+    fn sleep(&mut self, duration: Duration) {
+        self(duration)
+    }
+}
+
+// ------ Policy ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    None,
+    Fixed(Duration),
+    // initial before the first retry, then twice as long each time, never more than max
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    // the wait before the given retry, the first retry is 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        match *self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 2_u32.checked_pow(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+                initial.checked_mul(factor).map_or(max, |delay| delay.min(max))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    pub attempts: u32, // at least 1, the first try counts
+    pub backoff: Backoff,
+}
+
+impl Policy {
+    pub fn new(attempts: u32, backoff: Backoff) -> Policy {
+        Policy { attempts: attempts.max(1), backoff }
+    }
+
+    pub fn once() -> Policy {
+        Policy::new(1, Backoff::None)
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy::once()
+    }
+}
+
+// ------ Attempts ------
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<E> {
+    Succeeded,
+    Missing, // an Option producer gave None
+    Failed(E),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt<E> {
+    pub producer: &'static str,
+    pub attempt: u32, // 1 for the first try of this producer
+    pub outcome: Outcome<E>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Success<T, E> {
+    pub value: T,
+    pub producer: &'static str,
+    pub index: usize, // of the producer, in the order they were added
+    pub attempts: Vec<Attempt<E>>, // all of them, the successful one last
+}
+
+// every producer failed on every attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exhausted<E> {
+    pub attempts: Vec<Attempt<E>>,
+}
+
+impl<E: fmt::Display> fmt::Display for Exhausted<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "all {} attempts failed", self.attempts.len())?;
+        for attempt in &self.attempts {
+            write!(f, "\n  {} (attempt {}): ", attempt.producer, attempt.attempt)?;
+            match &attempt.outcome {
+                Outcome::Succeeded => write!(f, "succeeded")?,
+                Outcome::Missing => write!(f, "nothing")?,
+                Outcome::Failed(error) => write!(f, "{}", error)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for Exhausted<E> {}
+
+// ------ Fallback ------
+
+// Err(None) is a missing value, Err(Some(error)) a failure
+type Producer<'a, T, E> = Box<dyn FnMut() -> Result<T, Option<E>> + 'a>;
+
+struct Step<'a, T, E> {
+    name: &'static str,
+    producer: Producer<'a, T, E>,
+    policy: Policy,
+}
+
+pub struct Fallback<'a, T, E> {
+    steps: Vec<Step<'a, T, E>>,
+}
+
+impl<'a, T, E> Default for Fallback<'a, T, E> {
+    fn default() -> Self {
+        Fallback { steps: Vec::new() }
+    }
+}
+
+impl<'a, T, E> Fallback<'a, T, E> {
+    pub fn new() -> Self {
+        Fallback::default()
+    }
+
+    fn step(mut self, name: &'static str, producer: Producer<'a, T, E>) -> Self {
+        self.steps.push(Step { name, producer, policy: Policy::once() });
+        self
+    }
+
+    pub fn option(self, name: &'static str, mut producer: impl FnMut() -> Option<T> + 'a) -> Self {
+        self.step(name, Box::new(move || producer().ok_or(None)))
+    }
+
+    pub fn result(self, name: &'static str, mut producer: impl FnMut() -> Result<T, E> + 'a) -> Self {
+        self.step(name, Box::new(move || producer().map_err(Some)))
+    }
+
+    // a producer that can't fail, the last resort like give_num() in closures.rs
+    pub fn value(self, name: &'static str, mut producer: impl FnMut() -> T + 'a) -> Self {
+        self.step(name, Box::new(move || Ok(producer())))
+    }
+
+    // the attempts and the backoff of the producer added last
+    pub fn retry(mut self, policy: Policy) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.policy = policy;
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    // tries the producers in order, it can be run again, eg: every time the value is needed
+    pub fn run(&mut self, sleeper: &mut impl Sleeper) -> Result<Success<T, E>, Exhausted<E>> {
+        let mut attempts = Vec::new();
+        for (index, step) in self.steps.iter_mut().enumerate() {
+            for attempt in 1..=step.policy.attempts.max(1) {
+                if attempt > 1 {
+                    sleeper.sleep(step.policy.backoff.delay(attempt - 1));
+                }
+                let (result, outcome) = match (step.producer)() {
+                    Ok(value) => (Some(value), Outcome::Succeeded),
+                    Err(None) => (None, Outcome::Missing),
+                    Err(Some(error)) => (None, Outcome::Failed(error)),
+                };
+                attempts.push(Attempt { producer: step.name, attempt, outcome });
+                if let Some(value) = result {
+                    return Ok(Success { value, producer: step.name, index, attempts });
+                }
+            }
+        }
+        Err(Exhausted { attempts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn backoff_delays() {
+        assert_eq!(Backoff::None.delay(3), Duration::ZERO);
+        assert_eq!(Backoff::Fixed(5 * MS).delay(3), 5 * MS);
+        let exponential = Backoff::Exponential { initial: 10 * MS, max: 70 * MS };
+        let delays: Vec<Duration> = (1..=5).map(|retry| exponential.delay(retry)).collect();
+        assert_eq!(delays, [10 * MS, 20 * MS, 40 * MS, 70 * MS, 70 * MS]);
+        // no overflow on a very late retry
+        assert_eq!(exponential.delay(200), 70 * MS);
+        assert_eq!(Policy::new(0, Backoff::None).attempts, 1);
+    }
+
+    #[test]
+    fn cache_then_disk_then_default() {
+        let cache: HashMap<&str, u32> = HashMap::new();
+        let disk_reads = Cell::new(0);
+        let read_disk = || {
+            disk_reads.set(disk_reads.get() + 1);
+            match disk_reads.get() {
+                1 | 2 => Err("disk busy"),
+                _ => Ok(42),
+            }
+        };
+        let mut slept = Vec::new();
+        let found = Fallback::new()
+            .option("cache", || cache.get("answer").copied())
+            .result("disk", read_disk)
+            .retry(Policy::new(4, Backoff::Exponential { initial: 10 * MS, max: 15 * MS }))
+            .value("default", || 0)
+            .run(&mut |duration| slept.push(duration))
+            .unwrap();
+
+        assert_eq!((found.value, found.producer, found.index), (42, "disk", 1));
+        let outcomes: Vec<(&str, u32, Outcome<&str>)> =
+            found.attempts.into_iter().map(|attempt| (attempt.producer, attempt.attempt, attempt.outcome)).collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("cache", 1, Outcome::Missing),
+                ("disk", 1, Outcome::Failed("disk busy")),
+                ("disk", 2, Outcome::Failed("disk busy")),
+                ("disk", 3, Outcome::Succeeded),
+            ]
+        );
+        // a sleep before each retry, none when moving to the next producer
+        assert_eq!(slept, [10 * MS, 15 * MS]);
+        assert_eq!(disk_reads.get(), 3);
+    }
+
+    #[test]
+    fn falls_through_to_the_default() {
+        let mut fallback: Fallback<i32, String> = Fallback::new()
+            .option("return_option", || None)
+            .retry(Policy::new(2, Backoff::Fixed(MS)))
+            .result("parse", || "x".parse::<i32>().map_err(|error| error.to_string()))
+            .value("give_num", || 25);
+        let mut slept = Vec::new();
+        let found = fallback.run(&mut |duration| slept.push(duration)).unwrap();
+        assert_eq!((found.value, found.producer, found.attempts.len()), (25, "give_num", 4));
+        assert_eq!(slept, [MS]);
+        // it can run again
+        assert_eq!(fallback.run(&mut |_| ()).unwrap().value, 25);
+        assert_eq!(fallback.len(), 3);
+    }
+
+    #[test]
+    fn every_attempt_is_reported_when_all_fail() {
+        let mut tries = 0;
+        let error = Fallback::<u8, String>::new()
+            .option("cache", || None)
+            .result("network", || {
+                tries += 1;
+                Err(format!("timeout {}", tries))
+            })
+            .retry(Policy::new(3, Backoff::None))
+            .run(&mut |_| ())
+            .unwrap_err();
+        assert_eq!(error.attempts.len(), 4);
+        assert_eq!(
+            error.to_string(),
+            "all 4 attempts failed\n  cache (attempt 1): nothing\n  network (attempt 1): timeout 1\n  network (attempt 2): timeout 2\n  network (attempt 3): timeout 3"
+        );
+        let mut empty: Fallback<u8, ()> = Fallback::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.run(&mut ThreadSleeper), Err(Exhausted { attempts: vec![] }));
+    }
+}
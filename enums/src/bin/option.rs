@@ -13,6 +13,11 @@ enum Option<T> {
 */
 
 #![allow(unused_variables)] // #! implies it applies to whole file
+use enums::option_ext::{OptionExt, OptionMutExt, OptionRefExt};
+
+// assert_eq! with true is kept to show what is_some() and is_none() return, a lint attribute on the
+// assert_eq! itself is ignored so it goes on main
+#[allow(clippy::bool_assert_comparison)]
 fn main(){
 
     let x = Some(45);
@@ -28,6 +33,7 @@ fn main(){
     // so as it can be Something or Nothing, we get an error as we cannot add if its Nothing
     // one way to perform this is to extract the 45 from Some(45)
 
+    #[allow(clippy::unnecessary_literal_unwrap)] // x is Some on purpose, to show unwrap()
     let b = a + x.unwrap(); // the unwrap() extracts T from Some(T)
     // only use the above is your sure the Option variant is Some(T)
     // let c = a + z.unwrap(); this doesn't show error while writing code
@@ -47,6 +53,7 @@ fn main(){
     // => Some(&mut T), this let's us edit value in Some
     let mut x = Some(10);
     // if x contains Some, then edit the value in x
+    #[allow(clippy::single_match, clippy::assign_op_pattern)]
     match x.as_mut(){
         // here k binds to the value in Some, hence k = 10
         Some(k) => *k = *k * 2,
@@ -56,6 +63,7 @@ fn main(){
 
     // Option in Match - Primitive Data Type
     let x = Some(5);
+    #[allow(clippy::single_match)]
     match x{
         Some(i) => println!("X contains {}",i),
         None => (),
@@ -65,6 +73,7 @@ fn main(){
 
     // Option in Match - Non Primtive
     let x = Some("Hello".to_string()); // "x" contains non primitive data
+    #[allow(clippy::single_match)]
     match x{ // match x, this will move "x" to match
         Some(i) => println!("X contains {}",i), 
         None => (),
@@ -73,6 +82,7 @@ fn main(){
     
     // Solving the Above using Clone
     let x = Some("Hello".to_string());
+    #[allow(clippy::single_match)]
     match x.clone(){ // match x, this will move "x" to match
         Some(i) => println!("X contains {}",i), 
         None => (),
@@ -81,6 +91,7 @@ fn main(){
 
     // Referencing Option - Better Approach
     let x = Some("Hello".to_string());
+    #[allow(clippy::single_match)]
     match &x{ // match x, this will move "x" to match
         Some(i) => println!("X contains {}",i), // Notice here "i" is &String
         None => (),
@@ -101,7 +112,31 @@ fn main(){
     // in match we have to do None => () or _ => (), so with if let, we don't need to write it
     // if-let is kinda synatic sugar of match, only used when we want to match a single pattern
 
-    
+    // ------ Extension traits, src/option_ext.rs ---------
+    // the same things as above in one line each
 
+    // as_mut() and match, with update
+    let mut x = Some(10);
+    x.as_mut().update(|k| *k *= 2);
+    assert_eq!(x,Some(20));
 
-}
\ No newline at end of file
+    // clone before match, with as_ref() nothing is cloned until it is needed
+    let x = Some("Hello".to_string());
+    println!("X contains {}", x.as_ref().describe());
+    let long: Option<String> = x.as_ref().cloned_if(|i| i.len() > 3);
+    println!("{:?} {:?}\n",x, long);
+
+    // two options into one value, when both are Some
+    // (std has an unstable zip_with of its own, so the trait is named)
+    let total = OptionExt::zip_with(Some(2), Some(3), |a, b| a + b);
+    assert_eq!(total,Some(5));
+
+    // a missing value is reported instead of silently ignored
+    let z: Option<i32> = None;
+    let c = a + z.unwrap_or_default_with_warning("z");
+    println!("a + z is {}", c);
+    match z.inspect_none(|| println!("z is None")).ok_or_log("z has no value"){
+        Ok(value) => println!("z is {}", value),
+        Err(error) => println!("could not use z: {}", error),
+    }
+}
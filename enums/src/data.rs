@@ -0,0 +1,206 @@
+// DataEnums: one value that can be any of a few types, like a value in a dynamic language
+//
+// each variant stores a value of another type, From puts a value in and TryFrom takes it out:
+//   let value = DataEnums::from(34.5);          // DataEnums::Float(34.5)
+//   let float = f32::try_from(value).unwrap();  // 34.5
+// asking for the wrong type gives the value back in the error, so nothing is lost
+// codec.rs -> DataEnums to bytes and back, to send them to another process
+use std::fmt;
+
+pub mod codec;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataEnums {
+    Integer(u8),
+    Float(f32),
+    Character(char),
+    Stringy(String),
+    Person { name: String, age: u8 }, // Person item stores named fields, like a struct
+    Structure(Home),                  // Structure item stores a Struct
+}
+
+// the home of a listing, with its address, price and status
+pub use crate::listing::Home;
+
+// the fields of DataEnums::Person as a struct, so they can be converted as one value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Person {
+    pub name: String,
+    pub age: u8,
+}
+
+impl DataEnums {
+    // the name of the type the value holds
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DataEnums::Integer(_) => "integer",
+            DataEnums::Float(_) => "float",
+            DataEnums::Character(_) => "character",
+            DataEnums::Stringy(_) => "string",
+            DataEnums::Person { .. } => "person",
+            DataEnums::Structure(_) => "home",
+        }
+    }
+}
+
+// a description of every variant, no _ arm: a new variant won't compile until it is described here
+pub fn process_datatype(datatype: &DataEnums) -> String {
+    match datatype {
+        DataEnums::Integer(integer) => format!("Integer with value {}", integer),
+        DataEnums::Float(float) => format!("Float with value {}", float),
+        DataEnums::Character(character) => format!("Character {:?}", character),
+        DataEnums::Stringy(string) => format!("String {:?} of {} chars", string, string.chars().count()),
+        DataEnums::Person { name, age } => format!("Person with name {}, age {}", name, age),
+        // the var name inside items can be anything, here i took it as "house"
+        DataEnums::Structure(house) => format!("Home at {}, price {}", house.address, house.price()),
+    }
+}
+
+impl fmt::Display for DataEnums {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataEnums::Integer(integer) => write!(f, "{}", integer),
+            DataEnums::Float(float) => write!(f, "{}", float),
+            DataEnums::Character(character) => write!(f, "{:?}", character),
+            DataEnums::Stringy(string) => write!(f, "{:?}", string),
+            DataEnums::Person { name, age } => write!(f, "{} ({})", name, age),
+            DataEnums::Structure(home) => write!(f, "{}", home),
+        }
+    }
+}
+
+// ------ Conversions ------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrongType {
+    pub expected: &'static str,
+    pub value: DataEnums,
+}
+
+impl fmt::Display for WrongType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {}, found {} {}", self.expected, self.value.type_name(), self.value)
+    }
+}
+
+impl std::error::Error for WrongType {}
+
+// From and TryFrom for the variants that store one value
+macro_rules! convert {
+    ($($variant:ident($t:ty) = $name:literal),*) => {
+        $(
+            impl From<$t> for DataEnums {
+                fn from(value: $t) -> DataEnums {
+                    DataEnums::$variant(value)
+                }
+            }
+
+            impl TryFrom<DataEnums> for $t {
+                type Error = WrongType;
+
+                fn try_from(value: DataEnums) -> Result<$t, WrongType> {
+                    match value {
+                        DataEnums::$variant(inner) => Ok(inner),
+                        other => Err(WrongType { expected: $name, value: other }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+convert!(Integer(u8) = "integer", Float(f32) = "float", Character(char) = "character", Stringy(String) = "string", Structure(Home) = "home");
+
+impl From<&str> for DataEnums {
+    fn from(value: &str) -> DataEnums {
+        DataEnums::Stringy(value.to_string())
+    }
+}
+
+impl From<Person> for DataEnums {
+    fn from(person: Person) -> DataEnums {
+        DataEnums::Person { name: person.name, age: person.age }
+    }
+}
+
+impl TryFrom<DataEnums> for Person {
+    type Error = WrongType;
+
+    fn try_from(value: DataEnums) -> Result<Person, WrongType> {
+        match value {
+            DataEnums::Person { name, age } => Ok(Person { name, age }),
+            other => Err(WrongType { expected: "person", value: other }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::listing::{Address, Currency, Money};
+
+    fn home() -> Home {
+        Home::new(Address::new("Lakeside 5", "Lugano", "Ticino", "CH"), Money::from_cents(500050, Currency::Chf))
+    }
+
+    fn person() -> Person {
+        Person { name: "Lucifer".to_string(), age: 20 }
+    }
+
+    fn every_variant() -> Vec<DataEnums> {
+        vec![
+            DataEnums::from(7_u8),
+            DataEnums::from(34.5_f32),
+            DataEnums::from('x'),
+            DataEnums::from("abc"),
+            DataEnums::from(person()),
+            DataEnums::from(home()),
+        ]
+    }
+
+    #[test]
+    fn round_trips_through_from_and_try_from() {
+        assert_eq!(u8::try_from(DataEnums::from(7_u8)), Ok(7));
+        assert_eq!(f32::try_from(DataEnums::from(34.5_f32)), Ok(34.5));
+        assert_eq!(char::try_from(DataEnums::from('x')), Ok('x'));
+        assert_eq!(String::try_from(DataEnums::from("abc".to_string())), Ok("abc".to_string()));
+        assert_eq!(Person::try_from(DataEnums::from(person())), Ok(person()));
+        assert_eq!(Home::try_from(DataEnums::from(home())), Ok(home()));
+    }
+
+    #[test]
+    fn the_wrong_type_gives_the_value_back() {
+        let error = u8::try_from(DataEnums::from(person())).unwrap_err();
+        assert_eq!(error, WrongType { expected: "integer", value: DataEnums::from(person()) });
+        assert_eq!(error.to_string(), "expected integer, found person Lucifer (20)");
+        assert!(Person::try_from(DataEnums::from('x')).is_err());
+    }
+
+    #[test]
+    fn every_variant_is_named_and_described() {
+        let values = every_variant();
+        let names: Vec<&str> = values.iter().map(DataEnums::type_name).collect();
+        assert_eq!(names, ["integer", "float", "character", "string", "person", "home"]);
+        let descriptions: Vec<String> = values.iter().map(process_datatype).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "Integer with value 7",
+                "Float with value 34.5",
+                "Character 'x'",
+                "String \"abc\" of 3 chars",
+                "Person with name Lucifer, age 20",
+                "Home at Lakeside 5, Lugano, Ticino, CH, price CHF 5000.50",
+            ]
+        );
+        let shown: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        assert_eq!(shown, ["7", "34.5", "'x'", "\"abc\"", "Lucifer (20)", "home at Lakeside 5, Lugano, Ticino, CH for CHF 5000.50"]);
+    }
+
+    #[test]
+    fn equality_compares_type_and_value() {
+        assert_eq!(DataEnums::from(7_u8), DataEnums::Integer(7));
+        assert_ne!(DataEnums::from(7_u8), DataEnums::from(7.0_f32));
+        assert_ne!(DataEnums::from(f32::NAN), DataEnums::from(f32::NAN)); // like f32 itself
+    }
+}
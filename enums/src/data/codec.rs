@@ -0,0 +1,644 @@
+// A binary encoding of DataEnums, so values can be sent to another process
+//
+// every value is a tag-length-value record:
+//   tag     1 byte, which variant it is
+//   length  4 bytes, little endian, the number of bytes in value
+//   value   length bytes
+//
+//   Integer    1 byte
+//   Float      4 bytes, the little endian bits of the f32, so NaN is kept as it is
+//   Character  4 bytes, the little endian char code
+//   Stringy    the UTF-8 bytes
+//   Person     two records: a Stringy with the name, then an Integer with the age
+//   Structure  four Stringy records with the street, city, region and country of the address,
+//              a Status record, then a Money record for every price, the current one last
+//
+// Money and Status records are only found inside a Structure:
+//   Money      8 bytes, the little endian cents, then the 3 letters of the currency code
+//   Status     1 byte: 0 ForSale, 1 UnderOffer, 2 Sold, 3 Withdrawn, a Sold one is followed by its Money bytes
+//
+// eg: Integer(7) -> [1, 1, 0, 0, 0, 7]
+use std::fmt;
+use std::io::{Read, Write};
+use std::ops::RangeInclusive;
+
+use super::{DataEnums, Home};
+use crate::error::{self, Context};
+use crate::listing::{Address, Currency, Money, Status};
+
+const INTEGER: u8 = 1;
+const FLOAT: u8 = 2;
+const CHARACTER: u8 = 3;
+const STRING: u8 = 4;
+const PERSON: u8 = 5;
+const HOME: u8 = 6;
+const MONEY: u8 = 7;
+const STATUS: u8 = 8;
+
+// the tags a value can start with, Money and Status are only found inside a Home
+const VALUE_TAGS: RangeInclusive<u8> = INTEGER..=HOME;
+const ALL_TAGS: RangeInclusive<u8> = INTEGER..=STATUS;
+
+const HEADER_LEN: usize = 5;
+// a record can't be longer, a corrupt length doesn't make the decoder wait for gigabytes
+pub const MAX_LENGTH: usize = 1 << 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    UnknownTag(u8),
+    Truncated { needed: usize, available: usize },
+    TooLong(usize),
+    WrongLength { tag: u8, length: usize }, // eg: a Float that is not 4 bytes
+    UnexpectedTag { expected: u8, found: u8 }, // a field of a Person or a Home is of the wrong type
+    TrailingBytes(usize),
+    InvalidUtf8,
+    InvalidChar(u32),
+    InvalidCurrency([u8; 3]),
+    InvalidStatus(u8),
+    MixedCurrencies, // the prices of a home are not all in one currency, or it has none
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnknownTag(tag) => write!(f, "unknown tag {}", tag),
+            DecodeError::Truncated { needed, available } => {
+                write!(f, "truncated data: {} bytes needed but only {} available", needed, available)
+            }
+            DecodeError::TooLong(length) => write!(f, "a record of {} bytes is longer than {}", length, MAX_LENGTH),
+            DecodeError::WrongLength { tag, length } => write!(f, "a record with tag {} can't be {} bytes long", tag, length),
+            DecodeError::UnexpectedTag { expected, found } => write!(f, "expected tag {} but found tag {}", expected, found),
+            DecodeError::TrailingBytes(count) => write!(f, "{} bytes left after the value", count),
+            DecodeError::InvalidUtf8 => write!(f, "a string is not valid UTF-8"),
+            DecodeError::InvalidChar(code) => write!(f, "{:#x} is not a char", code),
+            DecodeError::InvalidCurrency(code) => write!(f, "{:?} is not a currency", String::from_utf8_lossy(code)),
+            DecodeError::InvalidStatus(status) => write!(f, "{} is not a listing status", status),
+            DecodeError::MixedCurrencies => write!(f, "a home needs prices, all in one currency"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// a value, or a field of a Person or a Home, longer than MAX_LENGTH
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    TooLong(usize),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::TooLong(length) => write!(f, "a value of {} bytes is longer than {}", length, MAX_LENGTH),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+// ------ Encoding ------
+
+// on an error nothing is added to out
+pub fn encode(value: &DataEnums, out: &mut Vec<u8>) -> Result<(), EncodeError> {
+    let start = out.len();
+    let result = encode_value(value, out);
+    if result.is_err() {
+        out.truncate(start);
+    }
+    result
+}
+
+fn encode_value(value: &DataEnums, out: &mut Vec<u8>) -> Result<(), EncodeError> {
+    match value {
+        DataEnums::Integer(integer) => record(out, INTEGER, &[*integer]),
+        DataEnums::Float(float) => record(out, FLOAT, &float.to_bits().to_le_bytes()),
+        DataEnums::Character(character) => record(out, CHARACTER, &u32::from(*character).to_le_bytes()),
+        DataEnums::Stringy(string) => record(out, STRING, string.as_bytes()),
+        DataEnums::Person { name, age } => {
+            let mut fields = Vec::new();
+            record(&mut fields, STRING, name.as_bytes())?;
+            record(&mut fields, INTEGER, &[*age])?;
+            record(out, PERSON, &fields)
+        }
+        DataEnums::Structure(home) => {
+            let mut fields = Vec::new();
+            let address = &home.address;
+            for part in [&address.street, &address.city, &address.region, &address.country] {
+                record(&mut fields, STRING, part.as_bytes())?;
+            }
+            let status = match home.status() {
+                Status::ForSale => vec![0],
+                Status::UnderOffer => vec![1],
+                Status::Sold(price) => [&[2][..], &money(price)].concat(),
+                Status::Withdrawn => vec![3],
+            };
+            record(&mut fields, STATUS, &status)?;
+            for price in home.price_history() {
+                record(&mut fields, MONEY, &money(*price))?;
+            }
+            record(out, HOME, &fields)
+        }
+    }
+}
+
+pub fn to_bytes(value: &DataEnums) -> Result<Vec<u8>, EncodeError> {
+    let mut out = Vec::new();
+    encode(value, &mut out)?;
+    Ok(out)
+}
+
+fn money(amount: Money) -> Vec<u8> {
+    [&amount.cents().to_le_bytes()[..], amount.currency().code().as_bytes()].concat()
+}
+
+fn record(out: &mut Vec<u8>, tag: u8, value: &[u8]) -> Result<(), EncodeError> {
+    if value.len() > MAX_LENGTH {
+        return Err(EncodeError::TooLong(value.len()));
+    }
+    out.push(tag);
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value);
+    Ok(())
+}
+
+// ------ Decoding ------
+
+// the tag and the length of the record at the start of bytes, None when the header is not all there
+// a tag outside of tags is reported right away, even before the rest of the header is there
+fn header(bytes: &[u8], tags: RangeInclusive<u8>) -> Result<Option<(u8, usize)>, DecodeError> {
+    if let Some(&tag) = bytes.first() {
+        if !tags.contains(&tag) {
+            return Err(DecodeError::UnknownTag(tag));
+        }
+    }
+    let Some(header) = bytes.get(..HEADER_LEN) else { return Ok(None) };
+    let tag = header[0];
+    let length = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if length > MAX_LENGTH {
+        return Err(DecodeError::TooLong(length));
+    }
+    Ok(Some((tag, length)))
+}
+
+// the value at the start of bytes and the number of bytes it used, the rest is not looked at
+pub fn decode(bytes: &[u8]) -> Result<(DataEnums, usize), DecodeError> {
+    let truncated = |needed| DecodeError::Truncated { needed, available: bytes.len() };
+    let (tag, length) = header(bytes, VALUE_TAGS)?.ok_or(truncated(HEADER_LEN))?;
+    let end = HEADER_LEN + length;
+    let body = bytes.get(HEADER_LEN..end).ok_or(truncated(end))?;
+    Ok((decode_body(tag, body)?, end))
+}
+
+// exactly one value, nothing after it
+pub fn from_bytes(bytes: &[u8]) -> Result<DataEnums, DecodeError> {
+    let (value, used) = decode(bytes)?;
+    match bytes.len() - used {
+        0 => Ok(value),
+        left => Err(DecodeError::TrailingBytes(left)),
+    }
+}
+
+fn decode_body(tag: u8, body: &[u8]) -> Result<DataEnums, DecodeError> {
+    let wrong_length = DecodeError::WrongLength { tag, length: body.len() };
+    let value = match tag {
+        INTEGER => match body {
+            [integer] => DataEnums::Integer(*integer),
+            _ => return Err(wrong_length),
+        },
+        FLOAT => DataEnums::Float(f32::from_bits(u32::from_le_bytes(body.try_into().map_err(|_| wrong_length)?))),
+        CHARACTER => {
+            let code = u32::from_le_bytes(body.try_into().map_err(|_| wrong_length)?);
+            DataEnums::Character(char::from_u32(code).ok_or(DecodeError::InvalidChar(code))?)
+        }
+        STRING => DataEnums::Stringy(String::from_utf8(body.to_vec()).map_err(|_| DecodeError::InvalidUtf8)?),
+        PERSON => {
+            let mut fields = Fields(body);
+            let name = fields.next(STRING)?;
+            let age = fields.next(INTEGER)?;
+            fields.end()?;
+            match (name, age) {
+                (DataEnums::Stringy(name), DataEnums::Integer(age)) => DataEnums::Person { name, age },
+                _ => unreachable!("the tags were checked"),
+            }
+        }
+        HOME => {
+            let mut fields = Fields(body);
+            let mut parts = Vec::new();
+            for _ in 0..4 {
+                match fields.next(STRING)? {
+                    DataEnums::Stringy(part) => parts.push(part),
+                    _ => unreachable!("the tag was checked"),
+                }
+            }
+            let status = match fields.next_record(STATUS)? {
+                [0] => Status::ForSale,
+                [1] => Status::UnderOffer,
+                [2, amount @ ..] => Status::Sold(decode_money(amount)?),
+                [3] => Status::Withdrawn,
+                [status, ..] => return Err(DecodeError::InvalidStatus(*status)),
+                [] => return Err(DecodeError::WrongLength { tag: STATUS, length: 0 }),
+            };
+            let mut prices = Vec::new();
+            while !fields.0.is_empty() {
+                prices.push(decode_money(fields.next_record(MONEY)?)?);
+            }
+            let [street, city, region, country] = &parts[..] else { unreachable!("there are four parts") };
+            let address = Address::new(street, city, region, country);
+            DataEnums::Structure(Home::from_parts(address, status, prices).ok_or(DecodeError::MixedCurrencies)?)
+        }
+        _ => return Err(DecodeError::UnknownTag(tag)),
+    };
+    Ok(value)
+}
+
+fn decode_money(bytes: &[u8]) -> Result<Money, DecodeError> {
+    let wrong_length = DecodeError::WrongLength { tag: MONEY, length: bytes.len() };
+    let (cents, code): ([u8; 8], [u8; 3]) = match bytes.split_at_checked(8) {
+        Some((cents, code)) => (cents.try_into().map_err(|_| wrong_length)?, code.try_into().map_err(|_| wrong_length)?),
+        None => return Err(wrong_length),
+    };
+    let currency = std::str::from_utf8(&code).ok().and_then(Currency::from_code).ok_or(DecodeError::InvalidCurrency(code))?;
+    Ok(Money::from_cents(i64::from_le_bytes(cents), currency))
+}
+
+// the records inside a Person or a Home, read one after the other
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    // the value of the next record, which must have the expected tag
+    fn next_record(&mut self, expected: u8) -> Result<&'a [u8], DecodeError> {
+        let bytes = self.0;
+        let truncated = |needed| DecodeError::Truncated { needed, available: bytes.len() };
+        let (found, length) = header(bytes, ALL_TAGS)?.ok_or(truncated(HEADER_LEN))?;
+        if found != expected {
+            return Err(DecodeError::UnexpectedTag { expected, found });
+        }
+        let end = HEADER_LEN + length;
+        let body = bytes.get(HEADER_LEN..end).ok_or(truncated(end))?;
+        self.0 = &bytes[end..];
+        Ok(body)
+    }
+
+    fn next(&mut self, expected: u8) -> Result<DataEnums, DecodeError> {
+        let body = self.next_record(expected)?;
+        decode_body(expected, body)
+    }
+
+    fn end(&self) -> Result<(), DecodeError> {
+        match self.0.len() {
+            0 => Ok(()),
+            left => Err(DecodeError::TrailingBytes(left)),
+        }
+    }
+}
+
+// ------ Streaming ------
+
+// values from bytes that arrive in pieces of any size, eg: from a socket
+//   decoder.feed(&buffer[..read]);
+//   while let Some(value) = decoder.next_value()? { ... }
+// after an error the stream can't be trusted anymore, the bad record is left in the buffer
+// the values are read from start on, the bytes before it are only removed once they are
+// half of the buffer, so decoding many small values doesn't move the rest each time
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+    start: usize,
+}
+
+impl Decoder {
+    pub fn new() -> Decoder {
+        Decoder::default()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.start > self.buffer.len() / 2 {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    // the next value, None until all of its bytes arrived
+    pub fn next_value(&mut self) -> Result<Option<DataEnums>, DecodeError> {
+        let pending = self.pending();
+        let Some((tag, length)) = header(pending, VALUE_TAGS)? else { return Ok(None) };
+        let end = HEADER_LEN + length;
+        if pending.len() < end {
+            return Ok(None);
+        }
+        let value = decode_body(tag, &pending[HEADER_LEN..end])?;
+        self.start += end;
+        if self.start == self.buffer.len() {
+            self.buffer.clear();
+            self.start = 0;
+        }
+        Ok(Some(value))
+    }
+
+    fn pending(&self) -> &[u8] {
+        &self.buffer[self.start..]
+    }
+
+    // the bytes of a value that is not complete yet
+    pub fn buffered(&self) -> usize {
+        self.pending().len()
+    }
+}
+
+// ------ Files and sockets ------
+
+pub fn write_values(mut writer: impl Write, values: &[DataEnums]) -> error::Result<()> {
+    let mut bytes = Vec::new();
+    for (index, value) in values.iter().enumerate() {
+        encode(value, &mut bytes).with_context(|| format!("while encoding value {}", index + 1))?;
+    }
+    writer.write_all(&bytes).context("while writing values")?;
+    Ok(writer.flush()?)
+}
+
+// every value until the end of the reader, which must not stop inside a value
+pub fn read_values(mut reader: impl Read) -> error::Result<Vec<DataEnums>> {
+    let mut decoder = Decoder::new();
+    let mut values = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let read = reader.read(&mut buffer).context("while reading values")?;
+        if read == 0 {
+            break;
+        }
+        decoder.feed(&buffer[..read]);
+        while let Some(value) = decoder.next_value().with_context(|| format!("while decoding value {}", values.len() + 1))? {
+            values.push(value);
+        }
+    }
+    if decoder.buffered() > 0 {
+        // the header or the value that was cut off, decode() says which
+        let Err(error) = decode(decoder.pending()) else { unreachable!("a whole value would have been decoded") };
+        return Err(error).with_context(|| format!("while decoding value {}", values.len() + 1));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // xorshift64, just enough to make the random tests repeatable
+    struct Rng(u64);
+
+    impl Rng {
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            (self.next_u64() % bound as u64) as usize
+        }
+
+        fn string(&mut self) -> String {
+            let alphabet = ['a', 'Z', ' ', '7', 'é', 'ß', '🦀', '\0', '\n'];
+            (0..self.below(12)).map(|_| alphabet[self.below(alphabet.len())]).collect()
+        }
+
+        fn float(&mut self) -> f32 {
+            f32::from_bits(self.next_u64() as u32)
+        }
+
+        fn money(&mut self, currency: Currency) -> Money {
+            Money::from_cents(self.next_u64() as i64 >> self.below(64), currency)
+        }
+
+        fn home(&mut self) -> Home {
+            let address = Address::new(&self.string(), &self.string(), &self.string(), &self.string());
+            let currency = Currency::ALL[self.below(Currency::ALL.len())];
+            let mut home = Home::new(address, self.money(currency));
+            for _ in 0..self.below(4) {
+                home.change_price(self.money(currency)).unwrap();
+            }
+            let status = [Status::UnderOffer, Status::Sold(self.money(currency)), Status::Withdrawn][self.below(3)];
+            if self.below(2) == 0 {
+                home.set_status(status).unwrap();
+            }
+            home
+        }
+
+        fn value(&mut self) -> DataEnums {
+            match self.below(6) {
+                0 => DataEnums::Integer(self.next_u64() as u8),
+                1 => DataEnums::Float(self.float()),
+                2 => DataEnums::Character(char::from_u32(self.below(0x11_0000) as u32).unwrap_or('\u{fffd}')),
+                3 => DataEnums::Stringy(self.string()),
+                4 => DataEnums::Person { name: self.string(), age: self.next_u64() as u8 },
+                _ => DataEnums::Structure(self.home()),
+            }
+        }
+    }
+
+    fn encoded(value: &DataEnums) -> Vec<u8> {
+        to_bytes(value).unwrap()
+    }
+
+    // NaN != NaN, so the values are compared by their encoding
+    fn same(a: &DataEnums, b: &DataEnums) -> bool {
+        encoded(a) == encoded(b)
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(encoded(&DataEnums::Integer(7)), [1, 1, 0, 0, 0, 7]);
+        assert_eq!(encoded(&DataEnums::from("hi")), [4, 2, 0, 0, 0, b'h', b'i']);
+        assert_eq!(
+            encoded(&DataEnums::Person { name: "Al".to_string(), age: 20 }),
+            [5, 13, 0, 0, 0, 4, 2, 0, 0, 0, b'A', b'l', 1, 1, 0, 0, 0, 20]
+        );
+        let mut home = Home::new(Address::new("A", "B", "C", "D"), Money::from_cents(500050, Currency::Chf));
+        home.set_status(Status::Sold(Money::from_cents(-1, Currency::Chf))).unwrap();
+        let bytes = encoded(&DataEnums::Structure(home.clone()));
+        let mut expected = vec![6, 57, 0, 0, 0];
+        for part in [b'A', b'B', b'C', b'D'] {
+            expected.extend([4, 1, 0, 0, 0, part]);
+        }
+        expected.extend([8, 12, 0, 0, 0, 2, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, b'C', b'H', b'F']);
+        expected.extend([7, 11, 0, 0, 0, 0x52, 0xa1, 0x07, 0, 0, 0, 0, 0, b'C', b'H', b'F']);
+        assert_eq!(bytes, expected);
+        assert_eq!(from_bytes(&bytes), Ok(DataEnums::Structure(home)));
+    }
+
+    #[test]
+    fn too_long_is_an_error_not_a_panic() {
+        let long = "x".repeat(MAX_LENGTH + 1);
+        let mut out = vec![1, 2, 3];
+        assert_eq!(encode(&DataEnums::Stringy(long.clone()), &mut out), Err(EncodeError::TooLong(MAX_LENGTH + 1)));
+        assert_eq!(out, [1, 2, 3]);
+        // the name fits, the Person around it doesn't
+        let person = DataEnums::Person { name: long[1..].to_string(), age: 1 };
+        assert_eq!(to_bytes(&person), Err(EncodeError::TooLong(MAX_LENGTH + HEADER_LEN + HEADER_LEN + 1)));
+        let error = write_values(Vec::new(), &[DataEnums::Integer(1), person]).unwrap_err();
+        assert_eq!(error.to_string(), "while encoding value 2");
+    }
+
+    #[test]
+    fn random_values_round_trip() {
+        let mut rng = Rng(46);
+        for _ in 0..2000 {
+            let value = rng.value();
+            let bytes = encoded(&value);
+            let decoded = from_bytes(&bytes).unwrap();
+            assert!(same(&decoded, &value), "{:?} came back as {:?}", value, decoded);
+        }
+    }
+
+    #[test]
+    fn every_prefix_is_truncated() {
+        let mut rng = Rng(7);
+        for _ in 0..200 {
+            let bytes = encoded(&rng.value());
+            for end in 0..bytes.len() {
+                assert!(matches!(decode(&bytes[..end]), Err(DecodeError::Truncated { .. })), "{:?}", &bytes[..end]);
+            }
+        }
+    }
+
+    #[test]
+    fn stream_split_at_random_points() {
+        let mut rng = Rng(1);
+        let values: Vec<DataEnums> = (0..300).map(|_| rng.value()).collect();
+        let mut stream = Vec::new();
+        for value in &values {
+            encode(value, &mut stream).unwrap();
+        }
+
+        let mut decoder = Decoder::new();
+        let mut decoded = Vec::new();
+        let mut rest = &stream[..];
+        while !rest.is_empty() {
+            let (piece, after) = rest.split_at(rng.below(rest.len().min(40)) + 1);
+            decoder.feed(piece);
+            rest = after;
+            while let Some(value) = decoder.next_value().unwrap() {
+                decoded.push(value);
+            }
+        }
+        assert_eq!(decoder.buffered(), 0);
+        assert_eq!(decoded.len(), values.len());
+        assert!(decoded.iter().zip(&values).all(|(a, b)| same(a, b)));
+    }
+
+    #[test]
+    fn many_small_values_in_one_feed() {
+        // 300_000 values, moving the rest of the buffer after each one would take minutes
+        let mut stream = Vec::new();
+        for index in 0..300_000_u32 {
+            encode(&DataEnums::Integer(index as u8), &mut stream).unwrap();
+        }
+        let mut decoder = Decoder::new();
+        decoder.feed(&stream);
+        decoder.feed(&[1, 1]); // the start of one more
+        let mut count = 0;
+        while let Some(value) = decoder.next_value().unwrap() {
+            assert_eq!(value, DataEnums::Integer(count as u8));
+            count += 1;
+        }
+        assert_eq!((count, decoder.buffered()), (300_000, 2));
+        decoder.feed(&[0, 0, 0, 9]);
+        assert_eq!(decoder.next_value(), Ok(Some(DataEnums::Integer(9))));
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn random_bytes_never_panic() {
+        let mut rng = Rng(3);
+        for _ in 0..5000 {
+            // mostly a valid record with a byte changed, so the errors come from deep inside
+            let mut bytes = encoded(&rng.value());
+            let at = rng.below(bytes.len());
+            bytes[at] = rng.next_u64() as u8;
+            let _ = from_bytes(&bytes);
+            let mut decoder = Decoder::new();
+            decoder.feed(&bytes);
+            let _ = decoder.next_value();
+        }
+    }
+
+    #[test]
+    fn reads_and_writes_streams() {
+        let mut rng = Rng(11);
+        let values: Vec<DataEnums> = (0..500).map(|_| rng.value()).collect();
+        let mut file = Vec::new();
+        write_values(&mut file, &values).unwrap();
+        let read = read_values(&file[..]).unwrap();
+        assert!(read.len() == values.len() && read.iter().zip(&values).all(|(a, b)| same(a, b)));
+
+        let cut = &file[..file.len() - 1];
+        let error = read_values(cut).unwrap_err();
+        assert_eq!(error.chain().count(), 2);
+        assert!(error.to_string().starts_with("while decoding value 500"));
+        assert!(matches!(error.root_cause(), error::Error::Decode(DecodeError::Truncated { .. })));
+
+        let error = read_values(&[1, 1, 0, 0, 0, 7, 99][..]).unwrap_err();
+        assert_eq!(error::report(&error), "error: while decoding value 2\n  caused by: unknown tag 99");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(decode(&[9, 0, 0, 0, 0]), Err(DecodeError::UnknownTag(9)));
+        assert_eq!(decode(&[4, 3, 0, 0, 0, b'a']), Err(DecodeError::Truncated { needed: 8, available: 6 }));
+        assert_eq!(decode(&[1, 2, 0, 0, 0, 7, 7]), Err(DecodeError::WrongLength { tag: 1, length: 2 }));
+        assert_eq!(decode(&[4, 1, 0, 0, 0, 0xff]), Err(DecodeError::InvalidUtf8));
+        assert_eq!(decode(&[3, 4, 0, 0, 0, 0, 0xd8, 0, 0]), Err(DecodeError::InvalidChar(0xd800)));
+        assert_eq!(decode(&[4, 0, 0, 0, 0x10]), Err(DecodeError::TooLong(0x1000_0000)));
+        assert_eq!(from_bytes(&[1, 1, 0, 0, 0, 7, 0]), Err(DecodeError::TrailingBytes(1)));
+        // a Person whose first field is an Integer, not the name
+        assert_eq!(
+            decode(&[5, 6, 0, 0, 0, 1, 1, 0, 0, 0, 20]),
+            Err(DecodeError::UnexpectedTag { expected: STRING, found: INTEGER })
+        );
+        // a Person whose body ends inside its name
+        assert_eq!(decode(&[5, 6, 0, 0, 0, 4, 9, 0, 0, 0, b'A']), Err(DecodeError::Truncated { needed: 14, available: 6 }));
+
+        // homes: the address, a status, then at least one price
+        let home = |status: &[u8], prices: &[&[u8]]| {
+            let mut fields = Vec::new();
+            for part in ["street", "city", "region", "country"] {
+                record(&mut fields, STRING, part.as_bytes()).unwrap();
+            }
+            record(&mut fields, STATUS, status).unwrap();
+            for price in prices {
+                record(&mut fields, MONEY, price).unwrap();
+            }
+            let mut out = Vec::new();
+            record(&mut out, HOME, &fields).unwrap();
+            decode(&out).map(|(value, _)| value)
+        };
+        let chf = [&1_i64.to_le_bytes()[..], b"CHF"].concat();
+        let eur = [&1_i64.to_le_bytes()[..], b"EUR"].concat();
+        assert!(home(&[0], &[&chf, &chf]).is_ok());
+        assert_eq!(home(&[0], &[]), Err(DecodeError::MixedCurrencies));
+        assert_eq!(home(&[0], &[&chf, &eur]), Err(DecodeError::MixedCurrencies));
+        assert_eq!(home(&[9], &[&chf]), Err(DecodeError::InvalidStatus(9)));
+        assert_eq!(home(&[2], &[&chf]), Err(DecodeError::WrongLength { tag: MONEY, length: 0 }));
+        assert_eq!(home(&[0], &[&chf[..10]]), Err(DecodeError::WrongLength { tag: MONEY, length: 10 }));
+        assert_eq!(home(&[0], &[&[&chf[..8], b"XYZ"].concat()]), Err(DecodeError::InvalidCurrency(*b"XYZ")));
+        // Money is only a part of a home, not a value
+        assert_eq!(decode(&[7, 11, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, b'C', b'H', b'F']), Err(DecodeError::UnknownTag(7)));
+
+        // the decoder reports an unknown tag as soon as it sees it, it doesn't wait for more bytes
+        let mut decoder = Decoder::new();
+        decoder.feed(&[1, 1, 0]);
+        assert_eq!(decoder.next_value(), Ok(None));
+        decoder.feed(&[0, 0, 7, 42]);
+        assert_eq!(decoder.next_value(), Ok(Some(DataEnums::Integer(7))));
+        assert_eq!(decoder.next_value(), Err(DecodeError::UnknownTag(42)));
+        // Money and Status tags too, a stray one doesn't make it wait for a 16 MiB body
+        let mut decoder = Decoder::new();
+        decoder.feed(&[MONEY, 0xff, 0xff, 0]);
+        assert_eq!(decoder.next_value(), Err(DecodeError::UnknownTag(MONEY)));
+        let mut decoder = Decoder::new();
+        decoder.feed(&[STATUS]);
+        assert_eq!(decoder.next_value(), Err(DecodeError::UnknownTag(STATUS)));
+    }
+}
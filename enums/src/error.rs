@@ -0,0 +1,209 @@
+// Result and the one Error type of this crate
+//
+// Result<T, E> is the Option of things that can fail:
+//
+// enum Result<T, E> {
+//     Ok(T),
+//     Err(E),
+// }
+//
+// each module keeps its own error enum (PaymentError, DecodeError, ...) that says exactly what went wrong,
+// Error has a variant for each of them so one function can fail in several ways and still use ?:
+//   let count: u8 = text.parse()?;             // ParseIntError -> Error::ParseInt
+//   transaction.capture()?;                    // PaymentError -> Error::Payment
+//
+// context() wraps an error with what was being done when it happened, that builds a chain:
+//   file.read_to_end(&mut bytes).context("while reading values.bin")?;
+// and report() prints the whole chain:
+//   error: while reading values.bin
+//     caused by: No such file or directory (os error 2)
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+
+use crate::data::codec::{DecodeError, EncodeError};
+use crate::data::WrongType;
+use crate::listing::{ListingError, MoneyError};
+use crate::payment::{PaymentError, ValidationError};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    ParseInt(ParseIntError),
+    ParseFloat(ParseFloatError),
+    Payment(PaymentError),
+    Validation(ValidationError),
+    Money(MoneyError),
+    Listing(ListingError),
+    WrongType(Box<WrongType>), // boxed, it holds the whole value and would make every Result large
+    Decode(DecodeError),
+    Encode(EncodeError),
+    Context { message: String, source: Box<Error> },
+}
+
+impl Error {
+    // this error and then each cause, the first one is the outermost
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn StdError + 'static)> {
+        let mut next: Option<&(dyn StdError + 'static)> = Some(self);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.source();
+            Some(current)
+        })
+    }
+
+    // the error under all the context, the one that started it
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
+}
+
+// the variants that wrap another error show it as their own message,
+// so only Context adds a line to the chain
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::ParseInt(error) => write!(f, "{}", error),
+            Error::ParseFloat(error) => write!(f, "{}", error),
+            Error::Payment(error) => write!(f, "{}", error),
+            Error::Validation(error) => write!(f, "{}", error),
+            Error::Money(error) => write!(f, "{}", error),
+            Error::Listing(error) => write!(f, "{}", error),
+            Error::WrongType(error) => write!(f, "{}", error),
+            Error::Decode(error) => write!(f, "{}", error),
+            Error::Encode(error) => write!(f, "{}", error),
+            Error::Context { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Io(error) => error.source(),
+            Error::ParseInt(error) => error.source(),
+            Error::ParseFloat(error) => error.source(),
+            Error::Payment(error) => error.source(),
+            Error::Validation(error) => error.source(),
+            Error::Money(error) => error.source(),
+            Error::Listing(error) => error.source(),
+            Error::WrongType(error) => error.source(),
+            Error::Decode(error) => error.source(),
+            Error::Encode(error) => error.source(),
+            Error::Context { source, .. } => Some(source.as_ref()),
+        }
+    }
+}
+
+macro_rules! from_error {
+    ($($variant:ident($t:ty)),*) => {
+        $(
+            impl From<$t> for Error {
+                fn from(error: $t) -> Error {
+                    Error::$variant(error)
+                }
+            }
+        )*
+    };
+}
+
+from_error!(
+    Io(io::Error),
+    ParseInt(ParseIntError),
+    ParseFloat(ParseFloatError),
+    Payment(PaymentError),
+    Validation(ValidationError),
+    Money(MoneyError),
+    Listing(ListingError),
+    Decode(DecodeError),
+    Encode(EncodeError)
+);
+
+impl From<WrongType> for Error {
+    fn from(error: WrongType) -> Error {
+        Error::WrongType(Box::new(error))
+    }
+}
+
+// ------ Context ------
+
+pub trait Context<T> {
+    // eg: .context("while reading values.bin")
+    fn context(self, message: impl Into<String>) -> Result<T>;
+
+    // the message is only built when there is an error, eg: .with_context(|| format!("while charging {}", id))
+    fn with_context<M: Into<String>>(self, message: impl FnOnce() -> M) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+    fn context(self, message: impl Into<String>) -> Result<T> {
+        self.with_context(|| message)
+    }
+
+    fn with_context<M: Into<String>>(self, message: impl FnOnce() -> M) -> Result<T> {
+        self.map_err(|error| Error::Context { message: message().into(), source: Box::new(error.into()) })
+    }
+}
+
+// every error of the chain on its own line, the outermost first
+pub fn report(error: &Error) -> String {
+    let mut lines = error.chain().map(|cause| cause.to_string());
+    let mut text = format!("error: {}", lines.next().unwrap_or_default());
+    for cause in lines {
+        text.push_str(&format!("\n  caused by: {}", cause));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::{Action, State};
+
+    fn parse_age(text: &str) -> Result<u8> {
+        let age = text.trim().parse::<u8>()?;
+        Ok(age)
+    }
+
+    #[test]
+    fn question_mark_converts() {
+        assert_eq!(parse_age(" 20 ").unwrap(), 20);
+        assert!(matches!(parse_age("old"), Err(Error::ParseInt(_))));
+        let float: Result<f32> = "x".parse::<f32>().map_err(Error::from);
+        assert!(matches!(float, Err(Error::ParseFloat(_))));
+        let missing = std::fs::read("/this/file/does/not/exist").context("while reading it");
+        assert!(matches!(&missing, Err(Error::Context { source, .. }) if matches!(**source, Error::Io(_))));
+    }
+
+    #[test]
+    fn context_builds_a_chain() {
+        let capture: std::result::Result<State, PaymentError> =
+            Err(PaymentError::IllegalTransition { from: State::Created, action: Action::Capture });
+        let error = capture
+            .context("while capturing transaction 7")
+            .with_context(|| format!("while closing day {}", 3))
+            .unwrap_err();
+
+        let chain: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+        assert_eq!(chain, ["while closing day 3", "while capturing transaction 7", "can't Capture a transaction that is Created"]);
+        assert!(matches!(error.root_cause(), Error::Payment(PaymentError::IllegalTransition { .. })));
+        assert_eq!(
+            report(&error),
+            "error: while closing day 3\n  caused by: while capturing transaction 7\n  caused by: can't Capture a transaction that is Created"
+        );
+    }
+
+    #[test]
+    fn a_plain_error_is_one_line() {
+        let error = parse_age("300").unwrap_err();
+        assert_eq!(error.chain().count(), 1);
+        assert_eq!(report(&error), "error: number too large to fit in target type");
+    }
+}
@@ -0,0 +1,18 @@
+// The enums of main.rs and src/bin/option.rs put to work: payments, listings and a binary codec,
+// each module with its own error enum that the crate wide Error in error.rs wraps
+// main.rs reaches them with paths like enums::payment::Payment
+
+// the Payment enum with data in its variants, and the transactions paid with it
+pub mod payment;
+
+// DataEnums, a value that can be one of a few types, with conversions to and from them
+pub mod data;
+
+// homes for sale with exact Money prices, their status and price history, and a search over them
+pub mod listing;
+
+// extension traits on Option for what src/bin/option.rs writes out with match
+pub mod option_ext;
+
+// Result, the crate wide Error with a variant for each module's error, and context() to chain them
+pub mod error;
@@ -0,0 +1,496 @@
+// Homes listed for sale: where they are, what they cost, and what happened to them
+//
+// prices are Money, a whole number of cents with a currency, never a float:
+// 0.1 + 0.2 is 0.30000000000000004 in f64, and f32 can't even store 16777217.00
+//
+// a listing moves through these statuses, Sold is the end:
+//
+//   ForSale <--> UnderOffer --> Sold
+//      ^  \          |
+//      |   +---------+-----> Withdrawn
+//      +--------relist-----------+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+// ------ Money ------
+// ordered like their codes, so search results can be grouped by currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Currency {
+    Chf,
+    Eur,
+    Gbp,
+    Usd,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 4] = [Currency::Chf, Currency::Eur, Currency::Gbp, Currency::Usd];
+
+    // the ISO 4217 code, eg: "CHF"
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Chf => "CHF",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Usd => "USD",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Currency> {
+        Currency::ALL.into_iter().find(|currency| currency.code() == code)
+    }
+}
+
+// an amount in cents (the minor unit, all these currencies have 100 of them)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    cents: i64,
+    currency: Currency,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyError {
+    CurrencyMismatch { expected: Currency, found: Currency },
+    Overflow,
+    Invalid(String), // the text that is not an amount
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch { expected, found } => {
+                write!(f, "expected an amount in {} but it is in {}", expected.code(), found.code())
+            }
+            MoneyError::Overflow => write!(f, "the amount is too large"),
+            MoneyError::Invalid(text) => write!(f, "{:?} is not an amount", text),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+impl Money {
+    pub fn from_cents(cents: i64, currency: Currency) -> Money {
+        Money { cents, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Money {
+        Money::from_cents(0, currency)
+    }
+
+    // "5000", "5000.5", "5000.50" or "-3.25", at most two decimals: "0.001" is not rounded, it is an error
+    pub fn parse(text: &str, currency: Currency) -> Result<Money, MoneyError> {
+        let invalid = || MoneyError::Invalid(text.to_string());
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((_, "")) => return Err(invalid()), // "5000." has a point but no decimals
+            Some(parts) => parts,
+            None => (digits, ""),
+        };
+        let all_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if whole.is_empty() || fraction.len() > 2 || !all_digits(whole) || !all_digits(fraction) {
+            return Err(invalid());
+        }
+        let whole: i64 = whole.parse().map_err(|_| MoneyError::Overflow)?;
+        let fraction: i64 = format!("{:0<2}", fraction).parse().map_err(|_| invalid())?;
+        let cents = whole.checked_mul(100).and_then(|cents| cents.checked_add(fraction)).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(if negative { -cents } else { cents }, currency))
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.cents
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    fn same_currency(&self, other: Money) -> Result<(), MoneyError> {
+        match self.currency == other.currency {
+            true => Ok(()),
+            false => Err(MoneyError::CurrencyMismatch { expected: self.currency, found: other.currency }),
+        }
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let cents = self.cents.checked_add(other.cents).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(cents, self.currency))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let cents = self.cents.checked_sub(other.cents).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(cents, self.currency))
+    }
+
+    // the sum of amounts in one currency, zero for no amounts
+    pub fn sum<'a>(amounts: impl IntoIterator<Item = &'a Money>, currency: Currency) -> Result<Money, MoneyError> {
+        amounts.into_iter().try_fold(Money::zero(currency), |total, amount| total.checked_add(*amount))
+    }
+}
+
+// amounts in different currencies can't be compared without an exchange rate, so they are unordered
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Money) -> Option<Ordering> {
+        (self.currency == other.currency).then(|| self.cents.cmp(&other.cents))
+    }
+}
+
+// eg: "CHF 5000.50"
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let cents = self.cents.unsigned_abs();
+        write!(f, "{}{} {}.{:02}", sign, self.currency.code(), cents / 100, cents % 100)
+    }
+}
+
+// ------ Homes ------
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub region: String, // the area the city is in, eg: a canton or a county
+    pub country: String,
+}
+
+impl Address {
+    pub fn new(street: &str, city: &str, region: &str, country: &str) -> Address {
+        Address { street: street.to_string(), city: city.to_string(), region: region.to_string(), country: country.to_string() }
+    }
+
+    // the city or the region, ignoring case
+    pub fn is_in(&self, area: &str) -> bool {
+        let area = area.to_lowercase();
+        self.city.to_lowercase() == area || self.region.to_lowercase() == area
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}, {}, {}", self.street, self.city, self.region, self.country)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    ForSale,
+    UnderOffer,
+    Sold(Money), // the price it was sold for
+    Withdrawn,
+}
+
+impl Status {
+    pub fn can_become(&self, next: Status) -> bool {
+        matches!(
+            (self, next),
+            (Status::ForSale, Status::UnderOffer)
+                | (Status::UnderOffer, Status::ForSale)
+                | (Status::ForSale | Status::UnderOffer, Status::Sold(_) | Status::Withdrawn)
+                | (Status::Withdrawn, Status::ForSale)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListingError {
+    IllegalStatus { from: Status, to: Status },
+    NotForSale(Status), // the price of a sold or withdrawn home can't change
+    Money(MoneyError),
+}
+
+impl fmt::Display for ListingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListingError::IllegalStatus { from, to } => write!(f, "a home that is {:?} can't become {:?}", from, to),
+            ListingError::NotForSale(status) => write!(f, "the price of a home that is {:?} can't change", status),
+            ListingError::Money(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ListingError {}
+
+impl From<MoneyError> for ListingError {
+    fn from(error: MoneyError) -> ListingError {
+        ListingError::Money(error)
+    }
+}
+
+// every price the home was listed at is kept, the current one last
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Home {
+    pub address: Address,
+    status: Status,
+    prices: Vec<Money>, // never empty
+}
+
+impl Home {
+    pub fn new(address: Address, price: Money) -> Home {
+        Home { address, status: Status::ForSale, prices: vec![price] }
+    }
+
+    // for a home read back from storage, None when there is no price, or the prices
+    // and the price it was sold for are not all in one currency, like set_status checks
+    pub fn from_parts(address: Address, status: Status, prices: Vec<Money>) -> Option<Home> {
+        let currency = prices.first()?.currency;
+        let sold_in = match status {
+            Status::Sold(price) => Some(price.currency),
+            _ => None,
+        };
+        let one_currency = prices.iter().map(|price| price.currency).chain(sold_in).all(|other| other == currency);
+        one_currency.then_some(Home { address, status, prices })
+    }
+
+    pub fn price(&self) -> Money {
+        *self.prices.last().expect("a home always has a price")
+    }
+
+    pub fn price_history(&self) -> &[Money] {
+        &self.prices
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn change_price(&mut self, price: Money) -> Result<(), ListingError> {
+        if !matches!(self.status, Status::ForSale | Status::UnderOffer) {
+            return Err(ListingError::NotForSale(self.status));
+        }
+        self.price().same_currency(price)?;
+        self.prices.push(price);
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, status: Status) -> Result<(), ListingError> {
+        if !self.status.can_become(status) {
+            return Err(ListingError::IllegalStatus { from: self.status, to: status });
+        }
+        if let Status::Sold(price) = status {
+            self.price().same_currency(price)?;
+        }
+        self.status = status;
+        Ok(())
+    }
+
+    pub fn is_available(&self) -> bool {
+        matches!(self.status, Status::ForSale | Status::UnderOffer)
+    }
+}
+
+impl fmt::Display for Home {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "home at {} for {}", self.address, self.price())
+    }
+}
+
+// ------ Search ------
+
+// every condition that is set must hold, eg:
+//   Filter::new().area("Zurich").price_range(min..=max).available_only()
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    area: Option<String>,
+    price: Option<RangeInclusive<Money>>,
+    available_only: bool,
+}
+
+impl Filter {
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    pub fn area(mut self, area: &str) -> Filter {
+        self.area = Some(area.to_string());
+        self
+    }
+
+    // the current price, homes priced in another currency are never in the range
+    pub fn price_range(mut self, range: RangeInclusive<Money>) -> Filter {
+        self.price = Some(range);
+        self
+    }
+
+    pub fn available_only(mut self) -> Filter {
+        self.available_only = true;
+        self
+    }
+
+    pub fn matches(&self, home: &Home) -> bool {
+        self.area.as_ref().is_none_or(|area| home.address.is_in(area))
+            && self.price.as_ref().is_none_or(|range| range.contains(&home.price()))
+            && (!self.available_only || home.is_available())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Listings {
+    homes: Vec<Home>,
+}
+
+impl Listings {
+    pub fn new() -> Listings {
+        Listings::default()
+    }
+
+    pub fn add(&mut self, home: Home) {
+        self.homes.push(home);
+    }
+
+    pub fn homes(&self) -> &[Home] {
+        &self.homes
+    }
+
+    // the homes that match, grouped by currency and the cheapest first in each group
+    // (prices in different currencies don't compare), equal prices stay in the order they were added
+    pub fn search(&self, filter: &Filter) -> Vec<&Home> {
+        let mut found: Vec<&Home> = self.homes.iter().filter(|home| filter.matches(home)).collect();
+        found.sort_by_key(|home| (home.price().currency, home.price().cents));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chf(text: &str) -> Money {
+        Money::parse(text, Currency::Chf).unwrap()
+    }
+
+    fn home(city: &str, region: &str, price: &str) -> Home {
+        Home::new(Address::new("Main Street 1", city, region, "CH"), chf(price))
+    }
+
+    #[test]
+    fn parses_and_shows_exact_amounts() {
+        assert_eq!(chf("5000.5").cents(), 500050);
+        assert_eq!(chf("5000.05").cents(), 500005);
+        assert_eq!(chf("-3").to_string(), "-CHF 3.00");
+        assert_eq!(chf("5000.5").to_string(), "CHF 5000.50");
+        for bad in ["", ".5", "5000.", "-3.", "1.234", "1,5", "1e3", "--1", "1.-5"] {
+            assert_eq!(Money::parse(bad, Currency::Chf), Err(MoneyError::Invalid(bad.to_string())), "{:?}", bad);
+        }
+        assert_eq!(Money::parse("99999999999999999999", Currency::Chf), Err(MoneyError::Overflow));
+        assert_eq!(Currency::from_code("GBP"), Some(Currency::Gbp));
+    }
+
+    #[test]
+    fn no_float_rounding() {
+        // 0.1 + 0.2 != 0.3 with floats
+        assert_ne!(0.1_f64 + 0.2, 0.3);
+        assert_eq!(chf("0.1").checked_add(chf("0.2")), Ok(chf("0.3")));
+
+        // ten times ten cents is exactly one franc
+        let dimes = vec![chf("0.10"); 10];
+        assert_eq!(Money::sum(&dimes, Currency::Chf), Ok(chf("1")));
+        assert_ne!((0..10).map(|_| 0.1_f32).sum::<f32>(), 1.0);
+
+        // f32 has 24 bits of precision, this price would lose its cents (and a franc)
+        let price = chf("16777217.01");
+        assert_eq!(price.to_string(), "CHF 16777217.01");
+        assert_eq!(16777217.01_f32, 16777218.0);
+
+        // a million small price changes add up exactly
+        let total = Money::sum(&vec![chf("0.01"); 1_000_000], Currency::Chf).unwrap();
+        assert_eq!(total, chf("10000"));
+        assert_eq!(chf("5000.50").checked_sub(chf("0.51")), Ok(chf("4999.99")));
+    }
+
+    #[test]
+    fn currencies_dont_mix() {
+        let euros = Money::parse("1", Currency::Eur).unwrap();
+        assert_eq!(chf("1").checked_add(euros), Err(MoneyError::CurrencyMismatch { expected: Currency::Chf, found: Currency::Eur }));
+        assert_eq!(chf("1").partial_cmp(&euros), None);
+        assert!(chf("1") < chf("1.01"));
+        assert_eq!(Money::from_cents(i64::MAX, Currency::Chf).checked_add(chf("0.01")), Err(MoneyError::Overflow));
+    }
+
+    #[test]
+    fn status_and_price_history() {
+        let mut listing = home("Zurich", "Zurich", "1200000");
+        listing.change_price(chf("1150000")).unwrap();
+        listing.set_status(Status::UnderOffer).unwrap();
+        listing.set_status(Status::ForSale).unwrap();
+        listing.change_price(chf("1099999.95")).unwrap();
+        assert_eq!(listing.price_history(), [chf("1200000"), chf("1150000"), chf("1099999.95")]);
+        assert_eq!(listing.price(), chf("1099999.95"));
+
+        listing.set_status(Status::Sold(chf("1090000"))).unwrap();
+        assert_eq!(listing.change_price(chf("1")), Err(ListingError::NotForSale(Status::Sold(chf("1090000")))));
+        assert_eq!(
+            listing.set_status(Status::ForSale),
+            Err(ListingError::IllegalStatus { from: Status::Sold(chf("1090000")), to: Status::ForSale })
+        );
+
+        let mut withdrawn = home("Bern", "Bern", "800000");
+        withdrawn.set_status(Status::Withdrawn).unwrap();
+        assert!(!withdrawn.is_available());
+        withdrawn.set_status(Status::ForSale).unwrap();
+        assert!(withdrawn.change_price(Money::parse("1", Currency::Usd).unwrap()).is_err());
+        assert_eq!(Home::from_parts(withdrawn.address.clone(), Status::ForSale, vec![]), None);
+        let sold_in_euros = Status::Sold(Money::parse("1", Currency::Eur).unwrap());
+        assert_eq!(Home::from_parts(withdrawn.address.clone(), sold_in_euros, vec![chf("1")]), None);
+        assert!(Home::from_parts(withdrawn.address.clone(), Status::Sold(chf("1")), vec![chf("2")]).is_some());
+    }
+
+    #[test]
+    fn search_groups_currencies_and_sorts_each() {
+        let mut listings = Listings::new();
+        let mut seed = 47_u64;
+        for index in 0..500 {
+            // a small linear congruential sequence is enough to shuffle the prices
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let currency = Currency::ALL[(seed >> 60) as usize % Currency::ALL.len()];
+            let price = Money::from_cents((seed >> 40) as i64 % 1000, currency);
+            listings.add(Home::new(Address::new(&index.to_string(), "Zurich", "Zurich", "CH"), price));
+        }
+        let found = listings.search(&Filter::new());
+        assert_eq!(found.len(), 500);
+        for pair in found.windows(2) {
+            let (a, b) = (pair[0].price(), pair[1].price());
+            assert!((a.currency, a.cents) <= (b.currency, b.cents), "{} before {}", a, b);
+            // equal prices keep the order they were added in
+            if a == b {
+                let order = |home: &Home| home.address.street.parse::<u32>().unwrap();
+                assert!(order(pair[0]) < order(pair[1]));
+            }
+        }
+        let currencies: Vec<Currency> = found.iter().map(|home| home.price().currency).collect();
+        assert!(currencies.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(Currency::ALL.iter().all(|currency| currencies.contains(currency)));
+    }
+
+    #[test]
+    fn search_by_area_and_price() {
+        let mut listings = Listings::new();
+        listings.add(home("Zurich", "Zurich", "1200000"));
+        listings.add(home("Winterthur", "Zurich", "650000.50"));
+        listings.add(home("Bern", "Bern", "700000"));
+        listings.add(home("Uster", "Zurich", "650000.49"));
+        listings.add(Home::new(Address::new("High Street 2", "Zurich", "Zurich", "CH"), Money::parse("700000", Currency::Eur).unwrap()));
+        let mut sold = home("Zurich", "Zurich", "900000");
+        sold.set_status(Status::Sold(chf("900000"))).unwrap();
+        listings.add(sold);
+
+        let cities = |filter: &Filter| -> Vec<String> {
+            listings.search(filter).iter().map(|home| format!("{} {}", home.address.city, home.price())).collect()
+        };
+        assert_eq!(
+            cities(&Filter::new().area("zurich").price_range(chf("650000.50")..=chf("1200000"))),
+            ["Winterthur CHF 650000.50", "Zurich CHF 900000.00", "Zurich CHF 1200000.00"]
+        );
+        assert_eq!(
+            cities(&Filter::new().area("ZURICH").price_range(chf("0")..=chf("1000000")).available_only()),
+            ["Uster CHF 650000.49", "Winterthur CHF 650000.50"]
+        );
+        assert_eq!(cities(&Filter::new().area("Bern")), ["Bern CHF 700000.00"]);
+        assert_eq!(listings.search(&Filter::new()).len(), 6);
+        assert!(listings.search(&Filter::new().area("Geneva")).is_empty());
+    }
+}
@@ -1,75 +1,109 @@
-#[allow(dead_code)]
 // an Enum contains a list of items
 // enums give you a way of saying a value is one of possible set of values
-// like the below example, Payment can be done using one of these options
-enum Payment{
-    Cash,
-    CreditCard,
-    DebitCard,
-}
+// like the Payment enum in src/payment.rs, Payment can be done using one of these options
+// enum Payment{
+//     Cash { tendered: u64 },
+//     CreditCard(Card),
+//     DebitCard(Card),
+// }
+use enums::data::codec::{read_values, write_values};
+use enums::data::{process_datatype, DataEnums, Home, Person};
+use enums::error::{report, Context, Result};
+use enums::listing::{Address, Currency, Money};
+use enums::payment::{format_cents, Card, Date, Expiry, Ledger, Payment};
 
 // -------- Enums that store a value within each item ------------
+// DataEnums in src/data.rs, each item stores a value:
+// enum DataEnums{
+//     Integer(u8),
+//     Float(f32),
+//     Character(char),
+//     Stringy(String),
+//     Person{name: String, age: u8},  // Person item stores named fields
+//     Structure(Home),                // Structure item stores a Struct
+// }
 // name of each enum variant that we define also becomes a function 
 // that constructs an instance of the enum
 // eg: DataEnums::Float() is an fn that takes f32 as arg and returns instance of DataEnum type
-#[allow(dead_code)]
-enum DataEnums{
-    Integer(u8),
-    Float(f32), 
-    Character(char),
-    Stringy(String),
-    Person{name: String, age: u8},  // Person item stores an enum, 
-    Structure(Home),                // Structure item stores a Struct
-}
-
-struct Home{
-    area: String,
-    cost: f32,
-}
 
 
+// main only prints the error, run() uses ? to hand every error up to it
 fn main() {
-    let payment_method = Payment::CreditCard;
+    if let Err(error) = run() {
+        eprintln!("{}", report(&error));
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let payment_method = Payment::CreditCard(Card::new("4111111111111111", Expiry { year: 2030, month: 12 }));
 
-    match payment_method{
-        Payment::Cash => println!("Paying with Cash"),
-        Payment::CreditCard => println!("Paying with Credit Card"),
-        Payment::DebitCard => println!("Paying with Debit Card"),
+    match &payment_method{
+        Payment::Cash { .. } => println!("Paying with Cash"),
+        Payment::CreditCard(card) => println!("Paying with Credit Card {}", card.masked()),
+        Payment::DebitCard(card) => println!("Paying with Debit Card {}", card.masked()),
     }
     // Note: the match statement should contain all the items of an Enum
     // Else it will throw an error, one way to avoid is use _
 
-    match payment_method{
-        Payment::Cash => println!("Paying with Cash"),
+    #[allow(clippy::single_match)] // if let is shorter, this shows the _ pattern
+    match &payment_method{
+        Payment::Cash { tendered } => println!("Paying with Cash, {} tendered", tendered),
         _ => (), // here _ -> every other case & "()" -> Do nothing
         // other => () -> this also works, we can replace "_" with any
     }
 
+    // a transaction moves from Created to Authorized to Captured, the ledger keeps them all
+    let mut ledger = Ledger::new();
+    let card_payment = ledger.create(payment_method, 1999);
+    let cash_payment = ledger.create(Payment::Cash { tendered: 2000 }, 1250);
+    for id in [card_payment, cash_payment] {
+        let transaction = ledger.get_mut(id)?;
+        transaction.authorize().with_context(|| format!("while authorizing transaction {}", id.0))?;
+        transaction.capture().with_context(|| format!("while capturing transaction {}", id.0))?;
+        if let Some(change) = transaction.change() {
+            println!("Change: {}", format_cents(change));
+        }
+    }
+    // charge() does all the steps, and says which one failed
+    let today = Date { year: 2026, month: 10, day: 19 };
+    let expired = Payment::DebitCard(Card::new("5500 0000 0000 0004", "01/2026".parse::<Expiry>()?));
+    if let Err(error) = ledger.charge(expired, 500, today) {
+        println!("{}", report(&error));
+    }
+    // capturing twice is not allowed
+    if let Err(error) = ledger.get_mut(card_payment).and_then(|transaction| transaction.capture()) {
+        println!("Error: {}", error);
+    }
+    for (method, total) in ledger.captured_by_method() {
+        println!("{:?}: {}", method, format_cents(total));
+    }
+
     // using enums that store values
     let person_item = DataEnums::Person { name: "Lucifer".to_string(), age: 20};
-    let structure_item = DataEnums::Structure(Home { area: "Swiz".to_string(), cost: 5000.5 });
+    let price = Money::parse("5000.50", Currency::Chf)?;
+    let structure_item = DataEnums::Structure(Home::new(Address::new("Lakeside 5", "Lugano", "Ticino", "CH"), price));
     let float_item = DataEnums::Float(34.5);
+    // From builds the item from the value it stores
+    let character_item = DataEnums::from('x');
 
-    process_datatype(person_item);
-    process_datatype(structure_item);
-    process_datatype(float_item);
+    // using enums that store values with match, see process_datatype in src/data.rs
+    let items = [person_item, structure_item, float_item, character_item];
+    for item in &items {
+        println!("{} -> {}", item.type_name(), process_datatype(item));
+    }
 
-    // using enums that store values with match
+    // TryFrom takes the value back out, when it is of that type
+    match f32::try_from(DataEnums::from(7_u8)) {
+        Ok(float) => println!("Float {}", float),
+        Err(error) => println!("Error: {}", error),
+    }
 
+    // the values as bytes and back, a Vec<u8> here but a file or a socket works the same
+    let mut bytes = Vec::new();
+    write_values(&mut bytes, &items)?;
+    let read_back = read_values(&bytes[..]).context("while reading the items back")?;
+    let person: Person = read_back[0].clone().try_into()?;
+    println!("{} values in {} bytes, the first one is {}", read_back.len(), bytes.len(), person.name);
+    Ok(())
 }
-
-fn process_datatype(datatype: DataEnums){
-    match datatype{
-        DataEnums::Person { name, age } => {
-            println!("Person with name {}, age {}",name, age);
-        }
-        // the var name inside items can be anything, here i took it as "house"
-        DataEnums::Structure(house) => {
-            println!("Home with place {}, cost {}",house.area, house.cost);
-        }
-        DataEnums::Float(my_float) => {
-            println!("Float with value {}",my_float);
-        }
-        _ => () 
-    }
-}
\ No newline at end of file
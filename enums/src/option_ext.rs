@@ -0,0 +1,189 @@
+// Shorter ways to write what src/bin/option.rs spells out with match
+//
+// OptionExt is for Option<T>, OptionRefExt for the Option<&T> and Option<&mut T>
+// that as_ref() and as_mut() give, so a value can be used without cloning or moving it:
+//
+//   match x.clone() { Some(i) => println!("{}", i), None => () }   // clones the whole value
+//   x.as_ref().cloned_if(|i| i.len() > 3)                          // clones only when needed
+//
+//   match x.as_mut() { Some(k) => *k = *k * 2, None => () }
+//   x.as_mut().update(|k| *k *= 2);
+//
+// ok_or_log and unwrap_or_default_with_warning print what was missing to stderr
+use std::fmt::{Debug, Display};
+
+pub trait OptionExt<T> {
+    // like ok_or, and prints the error when there is no value
+    fn ok_or_log<E: Display>(self, error: E) -> Result<T, E>;
+
+    // calls f when there is no value, the option is returned as it is
+    fn inspect_none(self, f: impl FnOnce()) -> Self;
+
+    // Some(f(a, b)) when both are Some, zip followed by map in one step
+    // std has an unstable Option::zip_with that does the same, so it is called as OptionExt::zip_with(a, b, f)
+    fn zip_with<U, R>(self, other: Option<U>, f: impl FnOnce(T, U) -> R) -> Option<R>;
+
+    // like unwrap_or_default, and prints a warning naming what was missing
+    fn unwrap_or_default_with_warning(self, what: &str) -> T
+    where
+        T: Default + Debug;
+
+    // changes the value in place, and drops it when f returns false
+    // the value that is left is returned, eg: decrement a counter and drop it at zero
+    fn filter_map_mut(&mut self, f: impl FnOnce(&mut T) -> bool) -> Option<&mut T>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn ok_or_log<E: Display>(self, error: E) -> Result<T, E> {
+        self.ok_or_else(|| {
+            eprintln!("error: {}", error);
+            error
+        })
+    }
+
+    fn inspect_none(self, f: impl FnOnce()) -> Self {
+        if self.is_none() {
+            f();
+        }
+        self
+    }
+
+    fn zip_with<U, R>(self, other: Option<U>, f: impl FnOnce(T, U) -> R) -> Option<R> {
+        Some(f(self?, other?))
+    }
+
+    fn unwrap_or_default_with_warning(self, what: &str) -> T
+    where
+        T: Default + Debug,
+    {
+        self.unwrap_or_else(|| {
+            let default = T::default();
+            eprintln!("warning: {} is missing, using {:?}", what, default);
+            default
+        })
+    }
+
+    fn filter_map_mut(&mut self, f: impl FnOnce(&mut T) -> bool) -> Option<&mut T> {
+        let keep = self.as_mut().is_some_and(f);
+        if !keep {
+            *self = None;
+        }
+        self.as_mut()
+    }
+}
+
+// for borrowed values, both Option<&T> and Option<&mut T>
+pub trait OptionRefExt<T: ?Sized> {
+    // the value, only while it is borrowed
+    fn get_ref(&self) -> Option<&T>;
+
+    // an owned copy, only of a value the predicate accepts
+    fn cloned_if(&self, predicate: impl FnOnce(&T) -> bool) -> Option<T::Owned>
+    where
+        T: ToOwned,
+    {
+        self.get_ref().filter(|value| predicate(value)).map(ToOwned::to_owned)
+    }
+
+    // an owned copy, or the default when there is no value, eg: Option<&str> -> String
+    fn to_owned_or_default(&self) -> T::Owned
+    where
+        T: ToOwned,
+        T::Owned: Default,
+    {
+        self.get_ref().map(ToOwned::to_owned).unwrap_or_default()
+    }
+
+    // a text for printing, without moving or cloning the value
+    fn describe(&self) -> String
+    where
+        T: Display,
+    {
+        match self.get_ref() {
+            Some(value) => format!("Some({})", value),
+            None => "None".to_string(),
+        }
+    }
+}
+
+impl<T: ?Sized> OptionRefExt<T> for Option<&T> {
+    fn get_ref(&self) -> Option<&T> {
+        *self
+    }
+}
+
+impl<T: ?Sized> OptionRefExt<T> for Option<&mut T> {
+    fn get_ref(&self) -> Option<&T> {
+        self.as_deref()
+    }
+}
+
+// only for Option<&mut T>: change the value where it is
+pub trait OptionMutExt<T: ?Sized> {
+    // calls f on the value, true when there was one
+    fn update(self, f: impl FnOnce(&mut T)) -> bool;
+}
+
+impl<T: ?Sized> OptionMutExt<T> for Option<&mut T> {
+    fn update(self, f: impl FnOnce(&mut T)) -> bool {
+        self.map(f).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_combinators() {
+        assert_eq!(Some(2).ok_or_log("no value"), Ok(2));
+        assert_eq!(None::<u8>.ok_or_log("no value"), Err("no value"));
+
+        let mut missing = 0;
+        assert_eq!(Some(1).inspect_none(|| missing += 1), Some(1));
+        assert_eq!(None::<u8>.inspect_none(|| missing += 1), None);
+        assert_eq!(missing, 1);
+
+        assert_eq!(OptionExt::zip_with(Some(3), Some(4), |a, b| a * b), Some(12));
+        assert_eq!(OptionExt::zip_with(Some(3), None::<u8>, |a, _| a), None);
+        assert_eq!(OptionExt::zip_with(None::<u8>, Some("x"), |a, _| a), None);
+
+        assert_eq!(Some(5).unwrap_or_default_with_warning("the count"), 5);
+        assert_eq!(None::<String>.unwrap_or_default_with_warning("the name"), "");
+    }
+
+    #[test]
+    fn filter_map_mut_changes_then_keeps_or_drops() {
+        let mut tickets = Some(2);
+        let decrement = |left: &mut u32| {
+            *left -= 1;
+            *left > 0
+        };
+        assert_eq!(tickets.filter_map_mut(decrement), Some(&mut 1));
+        assert_eq!(tickets, Some(1));
+        assert_eq!(tickets.filter_map_mut(decrement), None);
+        assert_eq!(tickets, None);
+        // there is nothing to call f on
+        assert_eq!(tickets.filter_map_mut(|_| unreachable!()), None);
+    }
+
+    #[test]
+    fn borrowed_options_are_not_moved() {
+        let x = Some("Hello".to_string());
+        assert_eq!(x.as_ref().cloned_if(|text| text.len() > 3), Some("Hello".to_string()));
+        assert_eq!(x.as_ref().cloned_if(|text| text.is_empty()), None);
+        assert_eq!(x.as_ref().describe(), "Some(Hello)");
+        assert_eq!(x.as_deref().to_owned_or_default(), "Hello");
+        assert_eq!(None::<&str>.to_owned_or_default(), "");
+        assert_eq!(None::<&u8>.describe(), "None");
+        // x was only borrowed
+        assert_eq!(x, Some("Hello".to_string()));
+
+        let mut x = Some(10);
+        assert!(x.as_mut().update(|k| *k *= 2));
+        assert_eq!(x.as_mut().describe(), "Some(20)");
+        let mut y: Option<u8> = None;
+        assert!(!y.as_mut().update(|k| *k *= 2));
+        assert_eq!(x.as_mut().cloned_if(|k| *k == 20), Some(20));
+    }
+}
@@ -0,0 +1,375 @@
+// Payments and the life of a transaction
+//
+// Payment is the enum from main.rs, now each variant carries the data it needs:
+// the cash handed over, or the card that pays.
+// A Transaction moves through the states below, any other move is rejected:
+//
+//   Created --authorize--> Authorized --capture--> Captured --refund--> Refunded
+//      |                       |
+//      +--------void-----------+-----> Voided
+//
+// amounts are in cents, so they are whole numbers and never get rounded
+// card.rs -> the Card in the card variants, and how its number and expiry are checked
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::error::{self, Context};
+
+pub mod card;
+
+pub use card::{Card, Date, Expiry, Issuer, ValidationError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payment {
+    Cash { tendered: u64 }, // the cents handed over, the change is given back on capture
+    CreditCard(Card),
+    DebitCard(Card),
+}
+
+// the kind of a payment without its data, the ledger sums the payments by it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Method {
+    Cash,
+    CreditCard,
+    DebitCard,
+}
+
+impl Payment {
+    // cash is always valid, a card must have a valid number and not be expired on "today"
+    pub fn validate(&self, today: Date) -> Result<(), ValidationError> {
+        match self {
+            Payment::Cash { .. } => Ok(()),
+            Payment::CreditCard(card) | Payment::DebitCard(card) => card.validate(today).map(|_| ()),
+        }
+    }
+
+    pub fn method(&self) -> Method {
+        match self {
+            Payment::Cash { .. } => Method::Cash,
+            Payment::CreditCard(_) => Method::CreditCard,
+            Payment::DebitCard(_) => Method::DebitCard,
+        }
+    }
+}
+
+impl fmt::Display for Payment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Payment::Cash { tendered } => write!(f, "cash ({} tendered)", format_cents(*tendered)),
+            Payment::CreditCard(card) => write!(f, "credit card {}", card.masked()),
+            Payment::DebitCard(card) => write!(f, "debit card {}", card.masked()),
+        }
+    }
+}
+
+// 1234 -> "12.34"
+pub fn format_cents(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+// ------ Transactions ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Created,
+    Authorized,
+    Captured,
+    Refunded,
+    Voided,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Authorize,
+    Capture,
+    Refund,
+    Void,
+}
+
+impl State {
+    // the state an action leads to, None when the action is not allowed from this state
+    pub fn after(self, action: Action) -> Option<State> {
+        match (self, action) {
+            (State::Created, Action::Authorize) => Some(State::Authorized),
+            (State::Authorized, Action::Capture) => Some(State::Captured),
+            (State::Captured, Action::Refund) => Some(State::Refunded),
+            (State::Created | State::Authorized, Action::Void) => Some(State::Voided),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentError {
+    IllegalTransition { from: State, action: Action },
+    InsufficientCash { tendered: u64, due: u64 },
+    UnknownTransaction(TransactionId),
+}
+
+impl fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaymentError::IllegalTransition { from, action } => write!(f, "can't {:?} a transaction that is {:?}", action, from),
+            PaymentError::InsufficientCash { tendered, due } => {
+                write!(f, "{} tendered but {} is due", format_cents(*tendered), format_cents(*due))
+            }
+            PaymentError::UnknownTransaction(id) => write!(f, "there is no transaction {}", id.0),
+        }
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TransactionId(pub u32);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+// only the methods below change a transaction, so it can't skip a state
+// or have its amount changed after it was captured
+pub struct Transaction {
+    id: TransactionId,
+    payment: Payment,
+    amount: u64, // in cents
+    state: State,
+    history: Vec<State>, // every state the transaction was in, the current one last
+}
+
+impl Transaction {
+    pub fn new(id: TransactionId, payment: Payment, amount: u64) -> Transaction {
+        Transaction { id, payment, amount, state: State::Created, history: vec![State::Created] }
+    }
+
+    pub fn id(&self) -> TransactionId {
+        self.id
+    }
+
+    pub fn payment(&self) -> &Payment {
+        &self.payment
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn history(&self) -> &[State] {
+        &self.history
+    }
+
+    // the cents to give back for a cash payment, None for cards
+    pub fn change(&self) -> Option<u64> {
+        match self.payment {
+            Payment::Cash { tendered } => Some(tendered.saturating_sub(self.amount)),
+            Payment::CreditCard(_) | Payment::DebitCard(_) => None,
+        }
+    }
+
+    fn apply(&mut self, action: Action) -> Result<State, PaymentError> {
+        let next = self.state.after(action).ok_or(PaymentError::IllegalTransition { from: self.state, action })?;
+        self.state = next;
+        self.history.push(next);
+        Ok(next)
+    }
+
+    // cash is authorized when enough was handed over
+    pub fn authorize(&mut self) -> Result<State, PaymentError> {
+        if let Payment::Cash { tendered } = self.payment {
+            if tendered < self.amount && self.state == State::Created {
+                return Err(PaymentError::InsufficientCash { tendered, due: self.amount });
+            }
+        }
+        self.apply(Action::Authorize)
+    }
+
+    pub fn capture(&mut self) -> Result<State, PaymentError> {
+        self.apply(Action::Capture)
+    }
+
+    pub fn refund(&mut self) -> Result<State, PaymentError> {
+        self.apply(Action::Refund)
+    }
+
+    pub fn void(&mut self) -> Result<State, PaymentError> {
+        self.apply(Action::Void)
+    }
+}
+
+// ------ Ledger ------
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    transactions: Vec<Transaction>,
+}
+
+impl Ledger {
+    pub fn new() -> Ledger {
+        Ledger::default()
+    }
+
+    pub fn create(&mut self, payment: Payment, amount: u64) -> TransactionId {
+        let id = TransactionId(self.transactions.len() as u32 + 1);
+        self.transactions.push(Transaction::new(id, payment, amount));
+        id
+    }
+
+    pub fn get(&self, id: TransactionId) -> Option<&Transaction> {
+        self.transactions.iter().find(|transaction| transaction.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: TransactionId) -> Result<&mut Transaction, PaymentError> {
+        self.transactions.iter_mut().find(|transaction| transaction.id == id).ok_or(PaymentError::UnknownTransaction(id))
+    }
+
+    // validates the payment, then authorizes and captures a new transaction for it
+    // a transaction that can't be authorized is voided, so it is never left half done
+    pub fn charge(&mut self, payment: Payment, amount: u64, today: Date) -> error::Result<TransactionId> {
+        let describe = |payment: &Payment| format!("while charging {} to {}", format_cents(amount), payment);
+        payment.validate(today).with_context(|| describe(&payment))?;
+        let description = describe(&payment);
+        let id = self.create(payment, amount);
+        let transaction = self.get_mut(id)?;
+        if let Err(error) = transaction.authorize() {
+            transaction.void()?;
+            return Err(error).with_context(|| format!("while authorizing transaction {}", id.0)).context(description);
+        }
+        transaction.capture().with_context(|| format!("while capturing transaction {}", id.0)).context(description)?;
+        Ok(id)
+    }
+
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    // the captured amount for each method, refunded and voided transactions don't count
+    pub fn captured_by_method(&self) -> BTreeMap<Method, u64> {
+        let mut totals = BTreeMap::new();
+        for transaction in self.transactions.iter().filter(|t| t.state == State::Captured) {
+            *totals.entry(transaction.payment.method()).or_insert(0) += transaction.amount;
+        }
+        totals
+    }
+
+    pub fn captured_total(&self) -> u64 {
+        self.captured_by_method().values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card() -> Card {
+        Card::new("4111111111111111", Expiry { year: 2030, month: 12 })
+    }
+
+    #[test]
+    fn card_numbers_are_masked() {
+        assert_eq!(card().masked(), "**** 1111");
+        assert!(!format!("{:?}", card()).contains("4111111111111111"));
+        assert_eq!(Payment::DebitCard(card()).to_string(), "debit card **** 1111");
+        assert_eq!(Payment::Cash { tendered: 2005 }.to_string(), "cash (20.05 tendered)");
+    }
+
+    #[test]
+    fn validates_cards_only() {
+        let today = Date { year: 2031, month: 1, day: 1 };
+        assert_eq!(Payment::Cash { tendered: 0 }.validate(today), Ok(()));
+        assert_eq!(
+            Payment::CreditCard(card()).validate(today),
+            Err(ValidationError::Expired(Expiry { year: 2030, month: 12 }))
+        );
+        assert_eq!(Payment::DebitCard(card()).validate(Date { year: 2030, month: 12, day: 31 }), Ok(()));
+    }
+
+    #[test]
+    fn transitions() {
+        let mut transaction = Transaction::new(TransactionId(1), Payment::CreditCard(card()), 1999);
+        assert_eq!(
+            transaction.capture(),
+            Err(PaymentError::IllegalTransition { from: State::Created, action: Action::Capture })
+        );
+        assert_eq!(transaction.authorize(), Ok(State::Authorized));
+        assert_eq!(transaction.capture(), Ok(State::Captured));
+        assert_eq!(transaction.void().unwrap_err().to_string(), "can't Void a transaction that is Captured");
+        assert_eq!(transaction.refund(), Ok(State::Refunded));
+        assert!(transaction.refund().is_err());
+        assert_eq!(transaction.history(), [State::Created, State::Authorized, State::Captured, State::Refunded]);
+
+        let mut voided = Transaction::new(TransactionId(2), Payment::CreditCard(card()), 500);
+        voided.authorize().unwrap();
+        assert_eq!(voided.void(), Ok(State::Voided));
+        assert!(voided.capture().is_err());
+    }
+
+    #[test]
+    fn cash_needs_enough_and_gives_change() {
+        let mut short = Transaction::new(TransactionId(1), Payment::Cash { tendered: 1000 }, 1250);
+        assert_eq!(short.authorize(), Err(PaymentError::InsufficientCash { tendered: 1000, due: 1250 }));
+        assert_eq!(short.state(), State::Created);
+
+        let paid = Transaction::new(TransactionId(2), Payment::Cash { tendered: 2000 }, 1250);
+        assert_eq!(paid.change(), Some(750));
+        assert_eq!(Transaction::new(TransactionId(3), Payment::DebitCard(card()), 1).change(), None);
+    }
+
+    #[test]
+    fn ledger_sums_captured_amounts_per_method() {
+        let mut ledger = Ledger::new();
+        let mut run = |payment: Payment, amount: u64, actions: &[Action]| {
+            let id = ledger.create(payment, amount);
+            let transaction = ledger.get_mut(id).unwrap();
+            for action in actions {
+                match action {
+                    Action::Authorize => transaction.authorize(),
+                    Action::Capture => transaction.capture(),
+                    Action::Refund => transaction.refund(),
+                    Action::Void => transaction.void(),
+                }
+                .unwrap();
+            }
+        };
+        let capture = [Action::Authorize, Action::Capture];
+        run(Payment::Cash { tendered: 5000 }, 4200, &capture);
+        run(Payment::Cash { tendered: 100 }, 100, &capture);
+        run(Payment::CreditCard(card()), 1999, &capture);
+        run(Payment::CreditCard(card()), 3000, &[Action::Authorize, Action::Capture, Action::Refund]);
+        run(Payment::DebitCard(card()), 700, &[Action::Authorize, Action::Void]);
+        run(Payment::DebitCard(card()), 800, &[Action::Authorize]);
+
+        let totals = ledger.captured_by_method();
+        assert_eq!(totals.into_iter().collect::<Vec<_>>(), [(Method::Cash, 4300), (Method::CreditCard, 1999)]);
+        assert_eq!(ledger.captured_total(), 6299);
+        assert_eq!(ledger.get_mut(TransactionId(99)).unwrap_err(), PaymentError::UnknownTransaction(TransactionId(99)));
+    }
+
+    #[test]
+    fn charge_reports_why_it_failed() {
+        let today = Date { year: 2026, month: 6, day: 1 };
+        let mut ledger = Ledger::new();
+        let id = ledger.charge(Payment::CreditCard(card()), 1999, today).unwrap();
+        assert_eq!(ledger.get(id).map(Transaction::state), Some(State::Captured));
+        assert_eq!(ledger.get(id).map(|transaction| (transaction.id(), transaction.amount())), Some((id, 1999)));
+        assert_eq!(ledger.get(id).map(Transaction::payment), Some(&Payment::CreditCard(card())));
+
+        let bad_card = Payment::DebitCard(Card::new("4111 1111 1111 1112", Expiry { year: 2030, month: 1 }));
+        let error = ledger.charge(bad_card, 500, today).unwrap_err();
+        assert_eq!(
+            error::report(&error),
+            "error: while charging 5.00 to debit card **** 1112\n  caused by: the card number is mistyped, its check digit is wrong"
+        );
+        assert_eq!(ledger.transactions().len(), 1);
+        let garbled = Payment::CreditCard(Card::new("41€1", Expiry { year: 2030, month: 1 }));
+        let error = ledger.charge(garbled, 500, today).unwrap_err();
+        assert!(matches!(error.root_cause(), error::Error::Validation(ValidationError::InvalidCharacter('€'))));
+
+        let error = ledger.charge(Payment::Cash { tendered: 100 }, 250, today).unwrap_err();
+        assert!(matches!(
+            error.root_cause(),
+            error::Error::Payment(PaymentError::InsufficientCash { tendered: 100, due: 250 })
+        ));
+        assert_eq!(error.chain().count(), 3);
+        assert_eq!(ledger.transactions()[1].state(), State::Voided);
+        assert_eq!(ledger.captured_total(), 1999);
+    }
+}
@@ -0,0 +1,302 @@
+// A payment card: its number and its expiry, and the checks a card must pass before it is used
+//
+// - the number may be written with spaces or dashes, "4111 1111-1111 1111"
+// - the last digit is a Luhn checksum of the others, it catches a mistyped digit
+//   and most swapped pairs of digits
+// - the first digits (the IIN, issuer identification number) say who issued the card,
+//   and each issuer uses only some lengths
+// - a card can be used until the end of its expiry month, "today" is passed in
+//   so the tests and the callers decide what day it is
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Context, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Expiry {
+    pub year: u16,
+    pub month: u8, // 1 to 12
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Expiry {
+    pub fn new(year: u16, month: u8) -> Option<Expiry> {
+        (1..=12).contains(&month).then_some(Expiry { year, month })
+    }
+
+    // valid through the last day of the expiry month
+    pub fn is_expired(&self, today: Date) -> bool {
+        (today.year, today.month) > (self.year, self.month)
+    }
+}
+
+// "MM/YYYY", as it is printed on the card
+impl FromStr for Expiry {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Expiry, Error> {
+        let (month, year) = text.split_once('/').unwrap_or((text, ""));
+        let month: u8 = month.trim().parse().with_context(|| format!("while reading the month of {:?}", text))?;
+        let year: u16 = year.trim().parse().with_context(|| format!("while reading the year of {:?}", text))?;
+        Ok(Expiry::new(year, month).ok_or(ValidationError::InvalidExpiry(Expiry { year, month }))?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Issuer {
+    Visa,
+    Mastercard,
+    AmericanExpress,
+    Discover,
+    Jcb,
+    DinersClub,
+}
+
+// (first prefix, last prefix, issuer), prefixes of the same length
+const IIN_RANGES: [(u32, u32, Issuer); 11] = [
+    (4, 4, Issuer::Visa),
+    (51, 55, Issuer::Mastercard),
+    (2221, 2720, Issuer::Mastercard),
+    (34, 34, Issuer::AmericanExpress),
+    (37, 37, Issuer::AmericanExpress),
+    (6011, 6011, Issuer::Discover),
+    (644, 649, Issuer::Discover),
+    (65, 65, Issuer::Discover),
+    (3528, 3589, Issuer::Jcb),
+    (300, 305, Issuer::DinersClub),
+    (36, 36, Issuer::DinersClub),
+];
+
+impl Issuer {
+    // the issuer of a number made of digits only
+    pub fn from_number(digits: &str) -> Option<Issuer> {
+        IIN_RANGES.iter().find_map(|(first, last, issuer)| {
+            let width = first.to_string().len();
+            let prefix: u32 = digits.get(..width)?.parse().ok()?;
+            (*first..=*last).contains(&prefix).then_some(*issuer)
+        })
+    }
+
+    pub fn lengths(&self) -> &'static [usize] {
+        match self {
+            Issuer::Visa => &[13, 16, 19],
+            Issuer::Mastercard => &[16],
+            Issuer::AmericanExpress => &[15],
+            Issuer::Discover | Issuer::Jcb => &[16, 17, 18, 19],
+            Issuer::DinersClub => &[14, 15, 16, 17, 18, 19],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    Empty,
+    InvalidCharacter(char),
+    UnknownIssuer,
+    InvalidLength { issuer: Issuer, length: usize },
+    ChecksumFailed,
+    InvalidExpiry(Expiry),
+    Expired(Expiry),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::Empty => write!(f, "the card number is empty"),
+            ValidationError::InvalidCharacter(c) => write!(f, "{:?} is not allowed in a card number", c),
+            ValidationError::UnknownIssuer => write!(f, "the card issuer is not known"),
+            ValidationError::InvalidLength { issuer, length } => {
+                write!(f, "a {:?} card number can't have {} digits", issuer, length)
+            }
+            ValidationError::ChecksumFailed => write!(f, "the card number is mistyped, its check digit is wrong"),
+            ValidationError::InvalidExpiry(expiry) => write!(f, "{:02}/{} is not a month", expiry.month, expiry.year),
+            ValidationError::Expired(expiry) => write!(f, "the card expired in {:02}/{}", expiry.month, expiry.year),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+// the digits of a card number, spaces and dashes are skipped
+pub fn parse_number(number: &str) -> Result<String, ValidationError> {
+    let mut digits = String::with_capacity(number.len());
+    for c in number.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' => (),
+            _ => return Err(ValidationError::InvalidCharacter(c)),
+        }
+    }
+    if digits.is_empty() {
+        return Err(ValidationError::Empty);
+    }
+    Ok(digits)
+}
+
+// from the right, every second digit is doubled (and 9 taken off when it is over 9),
+// the sum of all the digits must then be a multiple of 10
+pub fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    for (index, c) in digits.chars().rev().enumerate() {
+        let Some(mut digit) = c.to_digit(10) else { return false };
+        if index % 2 == 1 {
+            digit *= 2;
+            if digit > 9 {
+                digit -= 9;
+            }
+        }
+        sum += digit;
+    }
+    !digits.is_empty() && sum % 10 == 0
+}
+
+// the card number is private so it can't be printed by accident,
+// Debug and masked() only show the last four digits
+#[derive(Clone, PartialEq, Eq)]
+pub struct Card {
+    number: String,
+    pub expiry: Expiry,
+}
+
+impl Card {
+    // keeps the number as it is given, validate() checks it
+    pub fn new(number: &str, expiry: Expiry) -> Card {
+        Card { number: number.to_string(), expiry }
+    }
+
+    // a card whose number is checked and stored as digits only, the expiry is not checked
+    pub fn parse(number: &str, expiry: Expiry) -> Result<Card, ValidationError> {
+        let digits = parse_number(number)?;
+        check_digits(&digits)?;
+        Ok(Card { number: digits, expiry })
+    }
+
+    // the last four digits, anything else in an unchecked number is skipped
+    pub fn last_four(&self) -> String {
+        let mut last: Vec<char> = self.number.chars().rev().filter(char::is_ascii_digit).take(4).collect();
+        last.reverse();
+        last.into_iter().collect()
+    }
+
+    // eg: "**** 1111"
+    pub fn masked(&self) -> String {
+        format!("**** {}", self.last_four())
+    }
+
+    pub fn issuer(&self) -> Option<Issuer> {
+        Issuer::from_number(&parse_number(&self.number).ok()?)
+    }
+
+    // the number and the expiry, the issuer is returned when both are fine
+    pub fn validate(&self, today: Date) -> Result<Issuer, ValidationError> {
+        let issuer = check_digits(&parse_number(&self.number)?)?;
+        if Expiry::new(self.expiry.year, self.expiry.month).is_none() {
+            return Err(ValidationError::InvalidExpiry(self.expiry));
+        }
+        if self.expiry.is_expired(today) {
+            return Err(ValidationError::Expired(self.expiry));
+        }
+        Ok(issuer)
+    }
+}
+
+fn check_digits(digits: &str) -> Result<Issuer, ValidationError> {
+    let issuer = Issuer::from_number(digits).ok_or(ValidationError::UnknownIssuer)?;
+    if !issuer.lengths().contains(&digits.len()) {
+        return Err(ValidationError::InvalidLength { issuer, length: digits.len() });
+    }
+    if !luhn_valid(digits) {
+        return Err(ValidationError::ChecksumFailed);
+    }
+    Ok(issuer)
+}
+
+impl fmt::Debug for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Card").field("number", &self.masked()).field("expiry", &self.expiry).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: Date = Date { year: 2026, month: 6, day: 15 };
+
+    fn expiry() -> Expiry {
+        Expiry { year: 2028, month: 3 }
+    }
+
+    #[test]
+    fn parses_separators() {
+        assert_eq!(parse_number("4111 1111-1111 1111"), Ok("4111111111111111".to_string()));
+        assert_eq!(parse_number("4111.1111"), Err(ValidationError::InvalidCharacter('.')));
+        assert_eq!(parse_number(" - "), Err(ValidationError::Empty));
+    }
+
+    #[test]
+    fn luhn() {
+        assert!(luhn_valid("79927398713"));
+        assert!(!luhn_valid("79927398710"));
+        // swapping two digits is caught
+        assert!(!luhn_valid("79927398731"));
+        assert!(!luhn_valid(""));
+        assert!(!luhn_valid("12a4"));
+    }
+
+    #[test]
+    fn issuers_by_prefix() {
+        let cases = [
+            ("4111111111111111", Issuer::Visa),
+            ("5500000000000004", Issuer::Mastercard),
+            ("2221000000000009", Issuer::Mastercard),
+            ("378282246310005", Issuer::AmericanExpress),
+            ("6011111111111117", Issuer::Discover),
+            ("6500000000000002", Issuer::Discover),
+            ("3530111333300000", Issuer::Jcb),
+            ("30569309025904", Issuer::DinersClub),
+        ];
+        for (number, issuer) in cases {
+            assert_eq!(Card::parse(number, expiry()).unwrap().issuer(), Some(issuer), "{}", number);
+        }
+        assert_eq!(Issuer::from_number("2720999999999999"), Some(Issuer::Mastercard));
+        assert_eq!(Issuer::from_number("2721000000000000"), None);
+        assert_eq!(Issuer::from_number("1234"), None);
+    }
+
+    #[test]
+    fn validation_errors() {
+        let validate = |number: &str| Card::new(number, expiry()).validate(TODAY);
+        assert_eq!(validate("4111-1111-1111-1111"), Ok(Issuer::Visa));
+        assert_eq!(validate("4111 1111 1111 1112"), Err(ValidationError::ChecksumFailed));
+        assert_eq!(validate("3782822463100050"), Err(ValidationError::InvalidLength { issuer: Issuer::AmericanExpress, length: 16 }));
+        assert_eq!(validate("9111111111111111"), Err(ValidationError::UnknownIssuer));
+        assert_eq!(validate("4111_1111"), Err(ValidationError::InvalidCharacter('_')));
+        assert_eq!(Card::parse("4111 1111 1111 1111", expiry()).unwrap().last_four(), "1111");
+        // an unchecked number can hold anything, masking it must not panic
+        let odd = Card::new("1€11-2é", expiry());
+        assert_eq!(odd.masked(), "**** 1112");
+        assert_eq!(odd.validate(TODAY), Err(ValidationError::InvalidCharacter('€')));
+    }
+
+    #[test]
+    fn expiry_is_checked_against_today() {
+        let card = |year, month| Card::new("4111111111111111", Expiry { year, month });
+        assert!(card(2026, 6).validate(TODAY).is_ok()); // still valid during its last month
+        assert_eq!(card(2026, 5).validate(TODAY), Err(ValidationError::Expired(Expiry { year: 2026, month: 5 })));
+        assert_eq!(card(2027, 13).validate(TODAY), Err(ValidationError::InvalidExpiry(Expiry { year: 2027, month: 13 })));
+        assert_eq!(Expiry::new(2027, 0), None);
+        assert_eq!(ValidationError::Expired(Expiry { year: 2026, month: 5 }).to_string(), "the card expired in 05/2026");
+
+        assert_eq!("03/2028".parse::<Expiry>().unwrap(), expiry());
+        assert!(matches!("13/2028".parse::<Expiry>(), Err(Error::Validation(ValidationError::InvalidExpiry(_)))));
+        let error = "03-2028".parse::<Expiry>().unwrap_err();
+        assert_eq!(crate::error::report(&error), "error: while reading the month of \"03-2028\"\n  caused by: invalid digit found in string");
+    }
+}
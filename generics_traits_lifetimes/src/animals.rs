@@ -0,0 +1,422 @@
+// The animals from src/bin/traits.rs, organised with supertraits so they can live together
+//
+//                LivingThing
+//                     |
+//                  Animal          <- every animal, can be stored as Box<dyn Animal>
+//                 /      \
+//   MammalAnatomy  Mammal  Bird  BirdAnatomy
+//
+// "trait Mammal: Animal + MammalAnatomy" means a type can only implement Mammal if it
+// also implements Animal and MammalAnatomy, and a &dyn Mammal can call the methods of all three
+use std::any::Any;
+use std::fmt;
+
+// a trait with a default behaviour, the types below use it as it is
+pub trait LivingThing {
+    fn is_this_living(&self) -> bool {
+        true
+    }
+}
+
+pub trait MammalAnatomy {
+    fn how_many_legs(&self) -> u8;
+}
+
+// the weight a bird's wings can carry, above this it can't take off
+// (kg of body per m² of wing, the heaviest flying birds are around 20)
+pub const MAX_WING_LOADING: f32 = 25.0;
+
+// why a bird can or can't fly, see BirdAnatomy::flight()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flight {
+    Flies,
+    Flightless,   // the species doesn't fly at all, eg: penguins
+    MissingWings, // a flying species that lost a wing
+    TooHeavy,     // the wing loading is over MAX_WING_LOADING
+    InvalidMass,  // the mass is 0, negative, infinite or NaN, so the loading means nothing
+}
+
+// only the first three methods are required, the others are worked out from them
+pub trait BirdAnatomy {
+    fn how_many_wings(&self) -> u8;
+    fn mass_kg(&self) -> f32;
+    fn wing_area_m2(&self) -> f32; // both wings together
+
+    fn is_flightless(&self) -> bool {
+        false
+    }
+
+    // kg per m² of wing, infinite when the wing area is 0, and NaN when the mass is 0 too
+    fn wing_loading(&self) -> f32 {
+        self.mass_kg() / self.wing_area_m2()
+    }
+
+    // a wing area of 0, negative, infinite or NaN is no usable wing,
+    // and only a finite mass above 0 gives a loading worth comparing
+    fn flight(&self) -> Flight {
+        let area = self.wing_area_m2();
+        let mass = self.mass_kg();
+        if self.is_flightless() {
+            Flight::Flightless
+        } else if self.how_many_wings() < 2 || !(area.is_finite() && area > 0.0) {
+            Flight::MissingWings
+        } else if !(mass.is_finite() && mass > 0.0) {
+            Flight::InvalidMass
+        } else if self.wing_loading() <= MAX_WING_LOADING {
+            Flight::Flies
+        } else {
+            Flight::TooHeavy
+        }
+    }
+}
+
+// what an animal eats, carnivores can't share an enclosure with other species
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diet {
+    Carnivore,
+    Omnivore,
+    Piscivore, // fish
+}
+
+// which branch of the taxonomy an animal is on, holding the animal as that trait object
+// so the caller can use the Mammal or Bird methods without knowing the concrete type
+#[derive(Clone, Copy)]
+pub enum Kind<'a> {
+    Mammal(&'a dyn Mammal),
+    Bird(&'a dyn Bird),
+}
+
+// Any is a supertrait so a Box<dyn Animal> can be turned back into a Tiger, see downcast_ref() below
+// Debug lets a whole zoo be printed with {:?}
+pub trait Animal: LivingThing + Any + fmt::Debug {
+    fn species(&self) -> &'static str;
+
+    fn diet(&self) -> Diet;
+
+    fn kind(&self) -> Kind<'_>;
+
+    fn as_mammal(&self) -> Option<&dyn Mammal> {
+        match self.kind() {
+            Kind::Mammal(mammal) => Some(mammal),
+            Kind::Bird(_) => None,
+        }
+    }
+
+    fn as_bird(&self) -> Option<&dyn Bird> {
+        match self.kind() {
+            Kind::Bird(bird) => Some(bird),
+            Kind::Mammal(_) => None,
+        }
+    }
+}
+
+pub trait Mammal: Animal + MammalAnatomy {}
+
+pub trait Bird: Animal + BirdAnatomy {}
+
+// methods on the trait object itself, eg: zoo[0].downcast_ref::<Tiger>()
+impl dyn Animal {
+    pub fn is<T: Animal>(&self) -> bool {
+        (self as &dyn Any).is::<T>()
+    }
+
+    pub fn downcast_ref<T: Animal>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref()
+    }
+
+    pub fn downcast_mut<T: Animal>(&mut self) -> Option<&mut T> {
+        (self as &mut dyn Any).downcast_mut()
+    }
+
+    // takes the animal out of the box, the box is given back when it holds another type
+    pub fn downcast<T: Animal>(self: Box<Self>) -> Result<Box<T>, Box<dyn Animal>> {
+        if self.is::<T>() {
+            let any: Box<dyn Any> = self;
+            Ok(any.downcast().expect("the type was checked above"))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tiger {
+    pub num_legs: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
+    pub num_legs: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eagle {
+    pub num_wings: u8,
+    pub mass_kg: f32,
+    pub wing_area_m2: f32,
+}
+
+// a bird that doesn't fly, whatever its wings look like
+#[derive(Debug, Clone, PartialEq)]
+pub struct Penguin {
+    pub mass_kg: f32,
+}
+
+impl Eagle {
+    // a healthy adult golden eagle
+    pub fn new() -> Eagle {
+        Eagle { num_wings: 2, mass_kg: 4.5, wing_area_m2: 0.65 }
+    }
+}
+
+impl Default for Eagle {
+    fn default() -> Eagle {
+        Eagle::new()
+    }
+}
+
+impl LivingThing for Tiger {}
+impl LivingThing for Monkey {}
+impl LivingThing for Eagle {}
+impl LivingThing for Penguin {}
+
+impl MammalAnatomy for Tiger {
+    fn how_many_legs(&self) -> u8 {
+        self.num_legs
+    }
+}
+
+impl MammalAnatomy for Monkey {
+    fn how_many_legs(&self) -> u8 {
+        self.num_legs
+    }
+}
+
+impl BirdAnatomy for Eagle {
+    fn how_many_wings(&self) -> u8 {
+        self.num_wings
+    }
+
+    fn mass_kg(&self) -> f32 {
+        self.mass_kg
+    }
+
+    fn wing_area_m2(&self) -> f32 {
+        self.wing_area_m2
+    }
+}
+
+impl BirdAnatomy for Penguin {
+    fn how_many_wings(&self) -> u8 {
+        2
+    }
+
+    fn mass_kg(&self) -> f32 {
+        self.mass_kg
+    }
+
+    // flippers, not wings
+    fn wing_area_m2(&self) -> f32 {
+        0.05
+    }
+
+    fn is_flightless(&self) -> bool {
+        true
+    }
+}
+
+impl Animal for Tiger {
+    fn species(&self) -> &'static str {
+        "Tiger"
+    }
+
+    fn diet(&self) -> Diet {
+        Diet::Carnivore
+    }
+
+    fn kind(&self) -> Kind<'_> {
+        Kind::Mammal(self)
+    }
+}
+
+impl Animal for Monkey {
+    fn species(&self) -> &'static str {
+        "Monkey"
+    }
+
+    fn diet(&self) -> Diet {
+        Diet::Omnivore
+    }
+
+    fn kind(&self) -> Kind<'_> {
+        Kind::Mammal(self)
+    }
+}
+
+impl Animal for Eagle {
+    fn species(&self) -> &'static str {
+        "Eagle"
+    }
+
+    fn diet(&self) -> Diet {
+        Diet::Carnivore
+    }
+
+    fn kind(&self) -> Kind<'_> {
+        Kind::Bird(self)
+    }
+}
+
+impl Animal for Penguin {
+    fn species(&self) -> &'static str {
+        "Penguin"
+    }
+
+    fn diet(&self) -> Diet {
+        Diet::Piscivore
+    }
+
+    fn kind(&self) -> Kind<'_> {
+        Kind::Bird(self)
+    }
+}
+
+impl Mammal for Tiger {}
+impl Mammal for Monkey {}
+impl Bird for Eagle {}
+impl Bird for Penguin {}
+
+// ------ Classification ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    Mammal { legs: u8 },
+    Bird { wings: u8, flight: Flight },
+}
+
+pub fn classify(animal: &dyn Animal) -> Classification {
+    match animal.kind() {
+        Kind::Mammal(mammal) => Classification::Mammal { legs: mammal.how_many_legs() },
+        Kind::Bird(bird) => Classification::Bird { wings: bird.how_many_wings(), flight: bird.flight() },
+    }
+}
+
+// ?Sized lets these take trait objects too, eg: is_this_mammal(zoo[0].as_ref())
+pub fn is_this_mammal<T: Animal + ?Sized>(animal: &T) -> bool {
+    animal.as_mammal().is_some()
+}
+
+pub fn can_fly<T>(bird: &T) -> bool
+where
+    T: BirdAnatomy + ?Sized,
+{
+    bird.flight() == Flight::Flies
+}
+
+// ------ Zoos ------
+// a zoo is any slice of boxed animals, eg: a Vec<Box<dyn Animal>>
+
+pub fn mammals(zoo: &[Box<dyn Animal>]) -> impl Iterator<Item = &dyn Mammal> {
+    zoo.iter().filter_map(|animal| animal.as_mammal())
+}
+
+pub fn birds(zoo: &[Box<dyn Animal>]) -> impl Iterator<Item = &dyn Bird> {
+    zoo.iter().filter_map(|animal| animal.as_bird())
+}
+
+// every animal of one concrete type, eg: all the tigers
+pub fn all_of<T: Animal>(zoo: &[Box<dyn Animal>]) -> impl Iterator<Item = &T> {
+    zoo.iter().filter_map(|animal| animal.downcast_ref::<T>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zoo() -> Vec<Box<dyn Animal>> {
+        vec![
+            Box::new(Tiger { num_legs: 4 }),
+            Box::new(Eagle::new()),
+            Box::new(Monkey { num_legs: 2 }),
+            Box::new(Tiger { num_legs: 3 }),
+        ]
+    }
+
+    #[test]
+    fn zoo_holds_every_kind() {
+        let zoo = zoo();
+        let species: Vec<&str> = zoo.iter().map(|animal| animal.species()).collect();
+        assert_eq!(species, ["Tiger", "Eagle", "Monkey", "Tiger"]);
+        assert!(zoo.iter().all(|animal| animal.is_this_living()));
+
+        let legs: Vec<u8> = mammals(&zoo).map(|mammal| mammal.how_many_legs()).collect();
+        assert_eq!(legs, [4, 2, 3]);
+        let wings: Vec<u8> = birds(&zoo).map(|bird| bird.how_many_wings()).collect();
+        assert_eq!(wings, [2]);
+        assert!(zoo[1].as_mammal().is_none());
+    }
+
+    #[test]
+    fn downcasts_to_the_concrete_type() {
+        let mut zoo = zoo();
+        assert!(zoo[0].is::<Tiger>());
+        assert_eq!(zoo[0].downcast_ref::<Tiger>(), Some(&Tiger { num_legs: 4 }));
+        assert_eq!(zoo[0].downcast_ref::<Monkey>(), None);
+
+        zoo[2].downcast_mut::<Monkey>().unwrap().num_legs = 4;
+        assert_eq!(all_of::<Monkey>(&zoo).collect::<Vec<_>>(), [&Monkey { num_legs: 4 }]);
+        assert_eq!(all_of::<Tiger>(&zoo).count(), 2);
+
+        let eagle = zoo.remove(1);
+        let eagle = eagle.downcast::<Tiger>().unwrap_err();
+        assert_eq!(*eagle.downcast::<Eagle>().unwrap(), Eagle::new());
+    }
+
+    #[test]
+    fn classifies_mammals() {
+        assert_eq!(classify(&Tiger { num_legs: 4 }), Classification::Mammal { legs: 4 });
+        assert_eq!(classify(&Monkey { num_legs: 2 }), Classification::Mammal { legs: 2 });
+        assert!(is_this_mammal(&Tiger { num_legs: 4 }));
+        assert!(is_this_mammal(&Monkey { num_legs: 2 }));
+        assert!(!is_this_mammal(&Eagle::new()));
+        assert!(!is_this_mammal(&Penguin { mass_kg: 30.0 }));
+    }
+
+    #[test]
+    fn classifies_birds_from_their_anatomy() {
+        let eagle = Eagle::new();
+        assert_eq!(classify(&eagle), Classification::Bird { wings: 2, flight: Flight::Flies });
+        assert!(can_fly(&eagle));
+
+        let injured = Eagle { num_wings: 1, ..Eagle::new() };
+        assert_eq!(injured.flight(), Flight::MissingWings);
+        let overfed = Eagle { mass_kg: 20.0, ..Eagle::new() };
+        assert!(overfed.wing_loading() > MAX_WING_LOADING);
+        assert_eq!(overfed.flight(), Flight::TooHeavy);
+        let wingless = Eagle { num_wings: 0, wing_area_m2: 0.0, ..Eagle::new() };
+        assert_eq!(wingless.wing_loading(), f32::INFINITY);
+        assert!(!can_fly(&wingless));
+        // 0 / 0 is NaN, the missing wing is reported first
+        let empty = Eagle { mass_kg: 0.0, wing_area_m2: 0.0, ..Eagle::new() };
+        assert!(empty.wing_loading().is_nan());
+        assert_eq!(empty.flight(), Flight::MissingWings);
+        // a negative mass gives a negative loading, it must not count as light enough
+        for mass_kg in [f32::NAN, -4.5, 0.0, f32::INFINITY] {
+            let odd = Eagle { mass_kg, ..Eagle::new() };
+            assert_eq!(odd.flight(), Flight::InvalidMass, "mass {}", mass_kg);
+            assert!(!can_fly(&odd));
+        }
+
+        let penguin = Penguin { mass_kg: 30.0 };
+        assert_eq!(classify(&penguin), Classification::Bird { wings: 2, flight: Flight::Flightless });
+        assert!(!can_fly(&penguin));
+    }
+
+    #[test]
+    fn works_on_trait_objects() {
+        let zoo = zoo();
+        let mammals: Vec<bool> = zoo.iter().map(|animal| is_this_mammal(animal.as_ref())).collect();
+        assert_eq!(mammals, [true, false, true, true]);
+        let flyers = birds(&zoo).filter(|bird| can_fly(*bird)).count();
+        assert_eq!(flyers, 1);
+    }
+}
@@ -9,6 +9,9 @@ fn largest<T>(list: &[T]) -> &T {
 We read this definition as: the function largest is generic over some type T. 
 This function has one parameter named list, which is a slice of values of type T. 
 The largest function will return a reference to a value of the same type T. 
+It is written in src/stats.rs as
+fn largest<T: TotalOrd>(list: &[T]) -> Option<&T>
+T needs a bound to be compared, and an empty list has no largest value so it returns an Option
 
 ----- Generics for Structs --------
 struct Point<T>{
@@ -45,30 +48,23 @@ which holds a value of type T, and Err, which holds a value of type E
 */
 
 // ----- Generics in Method Implementations -------
-struct Point<T> {
-    x: T,
-    y: T,
-}
-
+// Point<T> lives in src/geometry/point.rs, it is declared as
+// struct Point<T> { x: T, y: T }
+//
 // By declaring T as a generic type after impl, Rust can identify that the 
 // type in the angle brackets in Point is a generic type rather than a concrete type
 // Methods written within an impl that declares the generic type will be defined 
 // on any instance of the type, no matter what concrete type ends up 
 // substituting for the generic type.
-
-impl<T> Point<T> {
-    fn x(&self) -> &T { // this function returns a reference to a generic type
-        &self.x
-    }
-}
-
-// Here the below call_me() method only works on the Point struct having the
-// type as u8
-impl Point<u8>{
-    fn extract_x(&self){
-        println!("X is {}",self.x);
-    }
-}
+// impl<T> Point<T> { fn x(&self) -> &T { &self.x } }
+//
+// An impl can also put a bound on T, then its methods only exist for those types
+// impl<T: Num> Point<T>   -> dot(), and the + - * operators, only for numbers
+// impl<T: Float> Point<T> -> distance(), only for f32 and f64
+// and an impl can be for one concrete type, then its methods only exist for that type
+// impl Point<u8> { fn extract_x(&self) { println!("X is {}", self.x); } }
+use generics_traits_lifetimes::geometry::{Circle, Point, PointN, Polygon, Shape};
+use generics_traits_lifetimes::stats::{largest, largest_by_key, top_k};
 
 struct NewPoint<X1, Y1>{
     x: X1,
@@ -86,16 +82,42 @@ impl<X1, Y1> NewPoint<X1, Y1> {
     }
 }
 fn main(){
+    // the same function works for a list of i32, f64 or &str
+    println!("The largest number is {:?}", largest(&[34, 50, 25, 100, 65]));
+    println!("The two largest floats are {:?}", top_k(&[1.5, 0.2, 9.75, 3.0], 2));
+    println!("The longest word is {:?}", largest_by_key(&["tiger", "monkey", "eagle"], |word| word.len()));
+
     let point_1 = Point{x: 20.0, y:50.0};
     let x = point_1.x;
+    println!("point_1 is {}, {:.2} away from the origin", point_1, point_1.distance(Point::ORIGIN));
     // point_1.extract_x(); fails as the method is only defined for type u8
     
+    #[allow(clippy::unnecessary_cast)]
     let point_2 = Point{x: 25 as u8, y:70 as u8};
     let x = point_2.x;
     point_2.extract_x();
+    // point_2.distance(point_2); fails as the method is only defined for float types
+    println!("point_2 doubled is {}", point_2 * 2);
+
+    // i32 -> f64 can't fail, f64 -> i32 rounds and fails for values an i32 can't hold
+    let point_f64: Point<f64> = Point::new(3, 4).into();
+    let point_i32 = Point::<i32>::try_from(point_f64 * 1.6);
+    println!("{} scaled by 1.6 and rounded is {:?}", point_f64, point_i32);
 
     let point_3 = NewPoint{x: 20, y: 90};
     let point_4 = NewPoint{x:4.3, y: 9.8};
     let point_5 = point_3.mixup(point_4);
     // point_5 contains the types a mix of point_4 and point_5
+
+    // ----- Const Generics -------
+    // PointN<T, const N: usize> is generic over a number too, the number of dimensions
+    // PointN<f64, 3> and PointN<f64, 2> are different types, adding them fails to compile
+    let point_6 = PointN([1.0, 2.0, 2.0]);
+    println!("{} is {} away from the origin", point_6, point_6.length());
+
+    // the same shapes work with integer and float coordinates
+    let triangle = Polygon::new(vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 3)]).unwrap();
+    println!("The triangle has area {} and perimeter {}", triangle.area(), triangle.perimeter());
+    let circle = Circle::new(Point::new(0.5, 0.5), 1.5);
+    println!("Does the circle contain (1.5, 1.5): {}", circle.contains(Point::new(1.5, 1.5)));
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+/*
+-------- LIFETIMES ------
+Every reference in Rust has a lifetime, the part of the program where the value it points to
+is still alive. Most of the time the compiler works them out by itself, but when a function
+takes references and returns one, it has to be told which input the result borrows from.
+
+fn longest<'a>(a: &'a str, b: &'a str) -> &'a str {}
+We read this as: for some lifetime 'a, both inputs live at least as long as 'a
+and the returned reference lives as long as 'a, i.e. as long as the shorter of the two inputs
+
+----- Lifetime Elision -----
+with one reference as input the compiler fills in the lifetime itself
+fn first_word(s: &str) -> &str {}  is the same as  fn first_word<'a>(s: &'a str) -> &'a str {}
+
+----- Lifetimes in Structs -----
+struct KeyValueView<'a> { pairs: Vec<(&'a str, &'a str)> }
+a struct holding references needs a lifetime too, the struct can't outlive the text it points into
+
+----- 'static -----
+&'static str lives for the whole program, eg: string literals "hello" are stored in the binary
+*/
+use generics_traits_lifetimes::text::{longest, split_fields, tokenize, KeyValueView, OwnedKeyValues};
+
+// the result borrows from "config" only, so it only needs the lifetime of config
+fn animal_name<'a>(config: &'a str, default: &str) -> &'a str {
+    match KeyValueView::parse(config).ok().and_then(|view| view.get("name")) {
+        Some(name) => name,
+        None => {
+            println!("no name, the default {:?} can't be returned as it may not live as long as config", default);
+            ""
+        }
+    }
+}
+
+fn main() {
+    let string_1 = String::from("Tiger");
+    let result;
+    {
+        let string_2 = String::from("Eagle!");
+        result = longest(&string_1, &string_2);
+        println!("The longest is {}", result);
+    }
+    // println!("{}", result); fails, result may point into string_2 which is dropped
+
+    let line = "Monkey, 2, omnivore";
+    for field in split_fields(line, ',') {
+        println!("field: {}", field);
+    }
+
+    for token in tokenize("legs = 4; name = \"Shere Khan\"").flatten() {
+        println!("{:?} {:?} at {}", token.kind, token.text, token.offset);
+    }
+
+    let config = String::from("name = Shere Khan\nlegs = 4");
+    println!("name: {}", animal_name(&config, "Tiger"));
+
+    // an owned copy has no lifetime, it can outlive the text it was parsed from
+    let owned: OwnedKeyValues = {
+        let text = String::from("wings = 2");
+        KeyValueView::parse(&text).unwrap().to_owned_pairs()
+    };
+    println!("wings: {:?}", owned.get("wings"));
+}
@@ -6,70 +6,19 @@ ame behavior if we can call the same methods on all of those types. Trait defini
 group method signatures together to define a set of behaviors necessary to accomplish some purpose.
 */
 
-struct Tiger{
-    num_legs: u8
-}
-
-struct Monkey{
-    num_legs: u8
-}
-
-struct Eagle{
-    num_wings: u8
-}
-
-// here we create a trait MammalAnatomy having the following functions
-// instead just method signature, we can also define a fn with default behaviour
-pub trait MammalAnatomy{
-    fn how_many_legs(&self);
-}
-
-// here we create a trait BirdAnatomy having the following functions
-pub trait BirdAnatomy{
-    fn how_many_wings(&self);
-}
-
-
-// here we create a trait LivingThing having the following functions
-// we also declared a default behaviour
-// pub imples we can use this trait outside of this file
-pub trait LivingThing{
-    fn is_this_living(&self){
-        println!("This is a living thing")
-    }
-}
-// here we are implementing MammalAnatomy trait on the type Tiger
-impl MammalAnatomy for Tiger{
-    fn how_many_legs(&self){
-        println!("Number of Legs: {}",self.num_legs)
-    }
-}
-
-// here we are implementing MammalAnatomy trait on the type Monkey
-impl MammalAnatomy for Monkey{
-    fn how_many_legs(&self){
-        println!("Number of Legs: {}",self.num_legs)
-    }
-}
-
-// here we are implementing BirdAnatomy trait on the type Eagle
-impl BirdAnatomy for Eagle{
-    fn how_many_wings(&self){
-        println!("Number of Wings: {}",self.num_wings)
-    }
-}
-
-impl LivingThing for Tiger {}
-impl LivingThing for Monkey {}
-impl LivingThing for Eagle {}
-
+// The traits and the types implementing them live in src/animals.rs, so other code can use them:
+// - MammalAnatomy, BirdAnatomy and LivingThing, their methods return the answer instead of printing it
+// - Animal: LivingThing is a supertrait, every Animal must also be a LivingThing
+// - Mammal and Bird build on Animal, so a Box<dyn Animal> can hold any of them
+// - is_this_mammal() and can_fly() decide from the animal, a penguin has wings but can't fly
+use generics_traits_lifetimes::animals::{self, can_fly, classify, is_this_mammal, Animal, BirdAnatomy, Eagle, Kind, LivingThing, MammalAnatomy, Monkey, Penguin, Tiger};
 
 // ---------- Trait Bounds -----------
 // Trait Bounds is restricting a generic type based on the trait implementation
 // Implementing a generic func that only takes the Types that implement a specific trait
 // the fn tells that, it is generic over any type "T", that implements the "Trait" "MammalAnatomy"
-fn is_this_mammal<T: MammalAnatomy> (mammal: &T){
-    println!("Yes this is a mammal");
+fn count_legs<T: MammalAnatomy> (mammals: &[T]) -> u32 {
+    mammals.iter().map(|mammal| mammal.how_many_legs() as u32).sum()
 }
 /*
 fn is_this_mammal<T: MammalAnatomy> (mammal: &T){}
@@ -105,30 +54,46 @@ where
     
 */
 
-// this function works on any generic type that implements BirdAnatomy trait
-fn can_fly<T> (bird: &T)
-where
-    T: BirdAnatomy
-{
-    println!("Birds can fly")
-}
+// animals::can_fly() is written with a where clause, it works on any type that implements BirdAnatomy
 
 fn main(){
     let tiger = Tiger{num_legs: 4};
-    tiger.how_many_legs();
-    tiger.is_this_living();
+    println!("Number of Legs: {}", tiger.how_many_legs());
+    println!("Is the tiger living: {}", tiger.is_this_living());
     
-    is_this_mammal(&tiger); // it works as tiger implements Trait MammalAnatomy
+    println!("Is the tiger a mammal: {}", is_this_mammal(&tiger));
 
     let monkey = Monkey{num_legs: 2};
-    monkey.how_many_legs();
-    monkey.is_this_living();
-    is_this_mammal(&monkey); // it works as monkey implements Trait MammalAnatomy
-
-    let eagle = Eagle{num_wings: 2};
-    eagle.is_this_living();
-    eagle.how_many_wings();
-    can_fly(&eagle);
-    // is_this_mammal(eagle); panics, as eagle doesn't implement the Trait MammalAnatomy
+    println!("Number of Legs: {}", monkey.how_many_legs());
+    println!("Is the monkey living: {}", monkey.is_this_living());
+    println!("Is the monkey a mammal: {}", is_this_mammal(&monkey));
+    // it works as both monkeys implement Trait MammalAnatomy
+    println!("Two monkeys have {} legs", count_legs(&[monkey.clone(), Monkey{num_legs: 2}]));
+
+    let eagle = Eagle::new();
+    println!("Is the eagle living: {}", eagle.is_this_living());
+    println!("Number of Wings: {}", eagle.how_many_wings());
+    println!("Can the eagle fly: {} ({:.1} kg per m² of wing)", can_fly(&eagle), eagle.wing_loading());
+    // count_legs(&[eagle]); fails to compile, as eagle doesn't implement the Trait MammalAnatomy
+
+    let penguin = Penguin{mass_kg: 30.0};
+    println!("Can the penguin fly: {} ({:?})", can_fly(&penguin), penguin.flight());
+
+    // ------ Trait Objects ------
+    // Tiger, Monkey and Eagle are different types, but they all implement Animal
+    // so a Box<dyn Animal> can hold any of them and they can be stored in the same Vec
+    let zoo: Vec<Box<dyn Animal>> = vec![Box::new(tiger), Box::new(monkey), Box::new(eagle), Box::new(penguin)];
+    for animal in &zoo {
+        match animal.kind() {
+            Kind::Mammal(mammal) => println!("{} is a mammal with {} legs", animal.species(), mammal.how_many_legs()),
+            Kind::Bird(bird) => println!("{} is a bird with {} wings", animal.species(), bird.how_many_wings()),
+        }
+        println!("  {:?}", classify(animal.as_ref()));
+    }
+    println!("The zoo has {} mammals", animals::mammals(&zoo).count());
 
-}
\ No newline at end of file
+    // downcasting gets the concrete type back from the trait object
+    if let Some(tiger) = zoo[0].downcast_ref::<Tiger>() {
+        println!("The first animal is {:?}", tiger);
+    }
+}
@@ -0,0 +1,15 @@
+// ------- Geometry -------------
+// Generic geometry over any number type, written once and used with i32, u8, f64...
+// num.rs   -> the Num and Float traits, the bounds that say "T is a number"
+// point.rs -> Point<T>, the generic 2D point from src/bin/generics.rs
+// point_n.rs -> PointN<T, N>, a point with any number of dimensions
+// shapes.rs -> segments, polygons, circles and boxes made of Point<T>
+pub mod num;
+pub mod point;
+pub mod point_n;
+pub mod shapes;
+
+pub use num::{Float, Num, Signed};
+pub use point::Point;
+pub use point_n::PointN;
+pub use shapes::{Aabb, Circle, Polygon, Segment, Shape};
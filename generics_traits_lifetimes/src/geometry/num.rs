@@ -0,0 +1,79 @@
+// A trait bound like T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + ... gets long,
+// so the operations a number needs are grouped in one trait, and a type is a number
+// when it implements it, eg: fn dot<T: Num>(..) instead of listing every operator
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// every integer and float type, &str or String are not numbers so they can't be used with Num
+pub trait Num:
+    Copy + PartialOrd + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_f64(self) -> f64;
+}
+
+// the numbers that can be negative, subtracting two unsigned numbers can overflow
+// so the shapes, which compare differences, only work with these
+pub trait Signed: Num + Neg<Output = Self> {}
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for isize {}
+impl Signed for f32 {}
+impl Signed for f64 {}
+
+// the numbers that have a square root and fractions, only f32 and f64
+pub trait Float: Signed + Div<Output = Self> {
+    const INFINITY: Self;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn from_f64(value: f64) -> Self;
+}
+
+// the same impl for every integer type, written once with a macro
+macro_rules! impl_num {
+    ($zero:literal, $one:literal, $($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: $t = $zero;
+                const ONE: $t = $one;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(0, 1, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_num!(0.0, 1.0, f32, f64);
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                const INFINITY: $t = <$t>::INFINITY;
+
+                fn sqrt(self) -> $t {
+                    <$t>::sqrt(self)
+                }
+
+                fn abs(self) -> $t {
+                    <$t>::abs(self)
+                }
+
+                fn from_f64(value: f64) -> $t {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
@@ -0,0 +1,210 @@
+// Point<T> is generic over any T, but the arithmetic is only implemented when T: Num,
+// and distance() only when T: Float, so a Point<&str> can be made but not added
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use super::num::{Float, Num};
+
+/// A point in 2D, generic over the type of its coordinates.
+///
+/// Adding points of a type that is not a number does not compile:
+///
+/// ```compile_fail
+/// use generics_traits_lifetimes::geometry::Point;
+/// let sum = Point::new("thirty", "sixty") + Point::new("one", "two");
+/// ```
+///
+/// Neither does the dot product:
+///
+/// ```compile_fail
+/// use generics_traits_lifetimes::geometry::Point;
+/// let dot = Point::new("thirty", "sixty").dot(Point::new("one", "two"));
+/// ```
+///
+/// And distance() needs a square root, so integer points don't have it:
+///
+/// ```compile_fail
+/// use generics_traits_lifetimes::geometry::Point;
+/// let distance = Point::new(0, 0).distance(Point::new(3, 4));
+/// ```
+///
+/// And extract_x() is only implemented for Point<u8>:
+///
+/// ```compile_fail
+/// use generics_traits_lifetimes::geometry::Point;
+/// Point::new(20.0, 50.0).extract_x();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+// methods for any T, no bound needed
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    pub fn x(&self) -> &T {
+        &self.x
+    }
+
+    pub fn y(&self) -> &T {
+        &self.y
+    }
+
+    // builds a point of another type by converting each coordinate
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Point<U> {
+        Point { x: f(self.x), y: f(self.y) }
+    }
+}
+
+// methods for numbers only
+impl<T: Num> Point<T> {
+    pub const ORIGIN: Point<T> = Point { x: T::ZERO, y: T::ZERO };
+
+    pub fn dot(self, other: Point<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // the length of the vector squared, it needs no square root so integers have it too
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn distance_squared(self, other: Point<T>) -> T {
+        (self - other).length_squared()
+    }
+}
+
+// methods for f32 and f64 only
+impl<T: Float> Point<T> {
+    pub fn length(self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(self, other: Point<T>) -> T {
+        self.distance_squared(other).sqrt()
+    }
+}
+
+// Here the below extract_x() method only works on the Point struct having the
+// type as u8, an impl can be for one concrete type instead of a bound
+impl Point<u8> {
+    pub fn extract_x(&self) {
+        println!("X is {}", self.x);
+    }
+}
+
+impl<T: Num> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl<T: Num> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point { x: self.x - other.x, y: self.y - other.y }
+    }
+}
+
+// multiplying by a scalar, eg: Point::new(1, 2) * 3
+impl<T: Num> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, scalar: T) -> Point<T> {
+        Point { x: self.x * scalar, y: self.y * scalar }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        fmt::Display::fmt(&self.x, f)?;
+        write!(f, ", ")?;
+        fmt::Display::fmt(&self.y, f)?;
+        write!(f, ")")
+    }
+}
+
+// every i32 fits in an f64, so this conversion can't fail
+impl From<Point<i32>> for Point<f64> {
+    fn from(point: Point<i32>) -> Point<f64> {
+        point.map(f64::from)
+    }
+}
+
+// the other way the coordinates are rounded, and NaN, infinity or a number
+// too large for an i32 is an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRangeError;
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "coordinate does not fit in an i32")
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+impl TryFrom<Point<f64>> for Point<i32> {
+    type Error = OutOfRangeError;
+
+    fn try_from(point: Point<f64>) -> Result<Point<i32>, OutOfRangeError> {
+        let convert = |value: f64| {
+            let rounded = value.round();
+            if rounded >= i32::MIN as f64 && rounded <= i32::MAX as f64 {
+                Ok(rounded as i32)
+            } else {
+                Err(OutOfRangeError)
+            }
+        };
+        Ok(Point { x: convert(point.x)?, y: convert(point.y)? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -6);
+        assert_eq!(a + b, Point::new(5, -4));
+        assert_eq!(a - b, Point::new(-3, 8));
+        assert_eq!(a * 3, Point::new(3, 6));
+        assert_eq!(a.dot(b), -8);
+        assert_eq!(Point::new(3_u8, 4).length_squared(), 25);
+        assert_eq!(Point::<i32>::ORIGIN, Point::new(0, 0));
+    }
+
+    #[test]
+    fn float_distance() {
+        assert_eq!(Point::new(0.0, 0.0).distance(Point::new(3.0, 4.0)), 5.0);
+        assert_eq!(Point::new(-3.0_f32, 4.0).length(), 5.0);
+        assert_eq!(Point::new(1.5, 2.0) * 2.0, Point::new(3.0, 4.0));
+    }
+
+    #[test]
+    fn display_uses_the_coordinate_format() {
+        assert_eq!(Point::new(1, -2).to_string(), "(1, -2)");
+        assert_eq!(format!("{:.2}", Point::new(0.5, 1.0 / 3.0)), "(0.50, 0.33)");
+        assert_eq!(Point::new("thirty", "sixty").to_string(), "(thirty, sixty)");
+    }
+
+    #[test]
+    fn conversions() {
+        let float: Point<f64> = Point::new(3, -7).into();
+        assert_eq!(float, Point::new(3.0, -7.0));
+        assert_eq!(Point::<i32>::try_from(Point::new(2.6, -1.4)), Ok(Point::new(3, -1)));
+        assert_eq!(Point::<i32>::try_from(Point::new(f64::NAN, 0.0)), Err(OutOfRangeError));
+        assert_eq!(Point::<i32>::try_from(Point::new(0.0, 1e10)), Err(OutOfRangeError));
+        assert_eq!(Point::new(1_u8, 2).map(i32::from), Point::new(1, 2));
+    }
+}
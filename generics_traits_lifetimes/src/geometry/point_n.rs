@@ -0,0 +1,165 @@
+// PointN<T, N> is a point in N dimensions, N is a const generic: a number known at compile time
+// that is part of the type, so a PointN<f64, 3> can't be added to a PointN<f64, 2>
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use super::num::{Float, Num};
+use super::point::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointN<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> PointN<T, N> {
+    pub const fn new(coords: [T; N]) -> PointN<T, N> {
+        PointN(coords)
+    }
+
+    pub fn coords(&self) -> &[T; N] {
+        &self.0
+    }
+
+    pub const fn dimensions(&self) -> usize {
+        N
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> PointN<U, N> {
+        PointN(self.0.map(f))
+    }
+}
+
+impl<T: Num, const N: usize> PointN<T, N> {
+    pub const ORIGIN: PointN<T, N> = PointN([T::ZERO; N]);
+
+    pub fn dot(self, other: PointN<T, N>) -> T {
+        self.0.iter().zip(other.0).fold(T::ZERO, |sum, (a, b)| sum + *a * b)
+    }
+
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn distance_squared(self, other: PointN<T, N>) -> T {
+        (self - other).length_squared()
+    }
+}
+
+impl<T: Float, const N: usize> PointN<T, N> {
+    pub fn length(self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(self, other: PointN<T, N>) -> T {
+        self.distance_squared(other).sqrt()
+    }
+}
+
+// zip_with applies f to the matching coordinates of two points
+fn zip_with<T: Copy, const N: usize>(a: [T; N], b: [T; N], f: impl Fn(T, T) -> T) -> [T; N] {
+    let mut out = a;
+    for (out, b) in out.iter_mut().zip(b) {
+        *out = f(*out, b);
+    }
+    out
+}
+
+impl<T: Num, const N: usize> Add for PointN<T, N> {
+    type Output = PointN<T, N>;
+
+    fn add(self, other: PointN<T, N>) -> PointN<T, N> {
+        PointN(zip_with(self.0, other.0, |a, b| a + b))
+    }
+}
+
+impl<T: Num, const N: usize> Sub for PointN<T, N> {
+    type Output = PointN<T, N>;
+
+    fn sub(self, other: PointN<T, N>) -> PointN<T, N> {
+        PointN(zip_with(self.0, other.0, |a, b| a - b))
+    }
+}
+
+impl<T: Num, const N: usize> Mul<T> for PointN<T, N> {
+    type Output = PointN<T, N>;
+
+    fn mul(self, scalar: T) -> PointN<T, N> {
+        self.map(|coord| coord * scalar)
+    }
+}
+
+impl<T, const N: usize> Index<usize> for PointN<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for PointN<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.0[axis]
+    }
+}
+
+impl<T: Num, const N: usize> Default for PointN<T, N> {
+    fn default() -> PointN<T, N> {
+        PointN::ORIGIN
+    }
+}
+
+impl<T: fmt::Display, const N: usize> fmt::Display for PointN<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (index, coord) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            fmt::Display::fmt(coord, f)?;
+        }
+        write!(f, ")")
+    }
+}
+
+// a Point<T> is the same as a PointN<T, 2>
+impl<T> From<Point<T>> for PointN<T, 2> {
+    fn from(point: Point<T>) -> PointN<T, 2> {
+        PointN([point.x, point.y])
+    }
+}
+
+impl<T> From<PointN<T, 2>> for Point<T> {
+    fn from(PointN([x, y]): PointN<T, 2>) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_in_any_dimension() {
+        let a = PointN([1, 2, 3]);
+        let b = PointN([4, 5, 6]);
+        assert_eq!(a + b, PointN([5, 7, 9]));
+        assert_eq!(b - a, PointN([3, 3, 3]));
+        assert_eq!(a * 2, PointN([2, 4, 6]));
+        assert_eq!(a.dot(b), 32);
+        assert_eq!(a[2], 3);
+        assert_eq!(PointN::<i64, 4>::default(), PointN([0; 4]));
+
+        let mut c = PointN([1.0, 2.0, 2.0, 0.0]);
+        assert_eq!(c.length(), 3.0);
+        c[3] = 4.0;
+        assert_eq!(c.distance(PointN::ORIGIN), 5.0);
+        assert_eq!(c.dimensions(), 4);
+    }
+
+    #[test]
+    fn converts_to_and_from_point() {
+        let point = Point::new(3, 4);
+        let n: PointN<i32, 2> = point.into();
+        assert_eq!(n, PointN([3, 4]));
+        assert_eq!(Point::from(n), point);
+        assert_eq!(PointN([1.5, -2.0, 0.25]).to_string(), "(1.5, -2, 0.25)");
+    }
+}
@@ -0,0 +1,355 @@
+// Shapes made of Point<T>, generic over integer and float coordinates
+//
+// the containment and intersection tests only add, subtract, multiply and compare,
+// so with integers they are exact, no rounding can put a point on the wrong side of a line.
+// area and perimeter can have fractions (and square roots) even for integer shapes,
+// so they are always returned as f64
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+
+use super::num::{Num, Signed};
+use super::point::Point;
+
+// every closed shape can be measured, and asked if a point is inside it
+// points on the edge count as inside
+pub trait Shape<T> {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn contains(&self, point: Point<T>) -> bool;
+    fn bounding_box(&self) -> Aabb<T>;
+}
+
+// PartialOrd has no min/max, because floats can be NaN
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+fn distance<T: Signed>(a: Point<T>, b: Point<T>) -> f64 {
+    a.distance_squared(b).to_f64().sqrt()
+}
+
+// the cross product of (a - o) and (b - o):
+// Greater -> o, a, b turn left, Less -> they turn right, Equal -> they are on one line
+fn orientation<T: Signed>(o: Point<T>, a: Point<T>, b: Point<T>) -> Ordering {
+    let (oa, ob) = (a - o, b - o);
+    let cross = oa.x * ob.y - oa.y * ob.x;
+    cross.partial_cmp(&T::ZERO).unwrap_or(Ordering::Equal)
+}
+
+// ------ Axis aligned bounding box ------
+// a rectangle with sides parallel to the axes, min is the bottom left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Num> Aabb<T> {
+    // any two opposite corners
+    pub fn new(a: Point<T>, b: Point<T>) -> Aabb<T> {
+        Aabb { min: Point::new(min(a.x, b.x), min(a.y, b.y)), max: Point::new(max(a.x, b.x), max(a.y, b.y)) }
+    }
+
+    // the smallest box around all the points, None when there are no points
+    pub fn from_points(points: impl IntoIterator<Item = Point<T>>) -> Option<Aabb<T>> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Aabb::new(first, first), |aabb, point| aabb.union(&Aabb::new(point, point))))
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+
+    pub fn union(&self, other: &Aabb<T>) -> Aabb<T> {
+        Aabb {
+            min: Point::new(min(self.min.x, other.min.x), min(self.min.y, other.min.y)),
+            max: Point::new(max(self.max.x, other.max.x), max(self.max.y, other.max.y)),
+        }
+    }
+
+    // boxes that only touch intersect
+    pub fn intersects(&self, other: &Aabb<T>) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    pub fn intersection(&self, other: &Aabb<T>) -> Option<Aabb<T>> {
+        self.intersects(other).then(|| Aabb {
+            min: Point::new(max(self.min.x, other.min.x), max(self.min.y, other.min.y)),
+            max: Point::new(min(self.max.x, other.max.x), min(self.max.y, other.max.y)),
+        })
+    }
+
+    // the point of the box closest to "point"
+    pub fn clamp(&self, point: Point<T>) -> Point<T> {
+        Point::new(max(self.min.x, min(point.x, self.max.x)), max(self.min.y, min(point.y, self.max.y)))
+    }
+}
+
+impl<T: Num> Shape<T> for Aabb<T> {
+    fn area(&self) -> f64 {
+        self.width().to_f64() * self.height().to_f64()
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width().to_f64() + self.height().to_f64())
+    }
+
+    fn contains(&self, point: Point<T>) -> bool {
+        self.min.x <= point.x && point.x <= self.max.x && self.min.y <= point.y && point.y <= self.max.y
+    }
+
+    fn bounding_box(&self) -> Aabb<T> {
+        *self
+    }
+}
+
+// ------ Segment ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<T> {
+    pub a: Point<T>,
+    pub b: Point<T>,
+}
+
+impl<T: Signed> Segment<T> {
+    pub fn new(a: Point<T>, b: Point<T>) -> Segment<T> {
+        Segment { a, b }
+    }
+
+    pub fn length(&self) -> f64 {
+        distance(self.a, self.b)
+    }
+
+    pub fn bounding_box(&self) -> Aabb<T> {
+        Aabb::new(self.a, self.b)
+    }
+
+    pub fn contains(&self, point: Point<T>) -> bool {
+        orientation(self.a, self.b, point) == Ordering::Equal && self.bounding_box().contains(point)
+    }
+
+    // segments that share an end point or overlap on one line intersect too
+    pub fn intersects(&self, other: &Segment<T>) -> bool {
+        let d1 = orientation(self.a, self.b, other.a);
+        let d2 = orientation(self.a, self.b, other.b);
+        let d3 = orientation(other.a, other.b, self.a);
+        let d4 = orientation(other.a, other.b, self.b);
+        // each segment has the ends of the other on both sides of it
+        let crossing = |x: Ordering, y: Ordering| x != Ordering::Equal && y != Ordering::Equal && x != y;
+        if crossing(d1, d2) && crossing(d3, d4) {
+            return true;
+        }
+        self.contains(other.a) || self.contains(other.b) || other.contains(self.a) || other.contains(self.b)
+    }
+}
+
+// ------ Circle ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Circle<T> {
+    pub center: Point<T>,
+    pub radius: T,
+}
+
+impl<T: Signed> Circle<T> {
+    pub fn new(center: Point<T>, radius: T) -> Circle<T> {
+        Circle { center, radius }
+    }
+
+    pub fn intersects(&self, other: &Circle<T>) -> bool {
+        let reach = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= reach * reach
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        self.contains(aabb.clamp(self.center))
+    }
+}
+
+impl<T: Signed> Shape<T> for Circle<T> {
+    fn area(&self) -> f64 {
+        PI * self.radius.to_f64() * self.radius.to_f64()
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius.to_f64()
+    }
+
+    fn contains(&self, point: Point<T>) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb<T> {
+        let corner = Point::new(self.radius, self.radius);
+        Aabb::new(self.center - corner, self.center + corner)
+    }
+}
+
+// ------ Polygon ------
+// the vertices in order around the edge, the last one connects back to the first
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polygon<T> {
+    vertices: Vec<Point<T>>,
+}
+
+impl<T: Signed> Polygon<T> {
+    // a polygon needs at least three vertices
+    pub fn new(vertices: Vec<Point<T>>) -> Option<Polygon<T>> {
+        (vertices.len() >= 3).then_some(Polygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point<T>] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Segment<T>> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().zip(next).map(|(a, b)| Segment::new(*a, *b))
+    }
+
+    // twice the area, positive when the vertices go counter-clockwise (the shoelace formula)
+    // it is exact for integer polygons, the real area can end in .5
+    pub fn signed_double_area(&self) -> T {
+        self.edges().fold(T::ZERO, |sum, edge| sum + edge.a.x * edge.b.y - edge.b.x * edge.a.y)
+    }
+
+    pub fn intersects_segment(&self, segment: &Segment<T>) -> bool {
+        self.contains(segment.a) || self.edges().any(|edge| edge.intersects(segment))
+    }
+
+    // one is inside the other, or their edges cross
+    pub fn intersects(&self, other: &Polygon<T>) -> bool {
+        self.contains(other.vertices[0])
+            || other.contains(self.vertices[0])
+            || other.edges().any(|edge| self.edges().any(|own| own.intersects(&edge)))
+    }
+}
+
+impl<T: Signed> Shape<T> for Polygon<T> {
+    fn area(&self) -> f64 {
+        self.signed_double_area().to_f64().abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.edges().map(|edge| edge.length()).sum()
+    }
+
+    // the winding number: how many times the edges go around the point, 0 means outside
+    fn contains(&self, point: Point<T>) -> bool {
+        let mut winding = 0;
+        for edge in self.edges() {
+            if edge.contains(point) {
+                return true;
+            }
+            if edge.a.y <= point.y {
+                if edge.b.y > point.y && orientation(edge.a, edge.b, point) == Ordering::Greater {
+                    winding += 1;
+                }
+            } else if edge.b.y <= point.y && orientation(edge.a, edge.b, point) == Ordering::Less {
+                winding -= 1;
+            }
+        }
+        winding != 0
+    }
+
+    fn bounding_box(&self) -> Aabb<T> {
+        Aabb::from_points(self.vertices.iter().copied()).expect("a polygon has vertices")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p<T>(x: T, y: T) -> Point<T> {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn boxes() {
+        let a = Aabb::new(p(4, 3), p(0, 0));
+        assert_eq!(a, Aabb { min: p(0, 0), max: p(4, 3) });
+        assert_eq!((a.area(), a.perimeter()), (12.0, 14.0));
+        assert!(a.contains(p(4, 0)) && !a.contains(p(5, 0)));
+
+        let b = Aabb::new(p(3, 2), p(6, 6));
+        assert_eq!(a.intersection(&b), Some(Aabb::new(p(3, 2), p(4, 3))));
+        assert_eq!(a.union(&b), Aabb::new(p(0, 0), p(6, 6)));
+        assert!(!a.intersects(&Aabb::new(p(5, 0), p(6, 1))));
+        assert_eq!(Aabb::from_points([p(1.0, 5.0), p(-2.0, 0.5), p(0.0, 7.0)]), Some(Aabb::new(p(-2.0, 0.5), p(1.0, 7.0))));
+        assert_eq!(Aabb::<u8>::from_points([]), None);
+    }
+
+    #[test]
+    fn segments() {
+        let s = Segment::new(p(0, 0), p(4, 4));
+        assert!(s.intersects(&Segment::new(p(0, 4), p(4, 0))));
+        assert!(s.intersects(&Segment::new(p(4, 4), p(9, 0)))); // shared end point
+        assert!(s.intersects(&Segment::new(p(2, 2), p(6, 6)))); // overlapping on one line
+        assert!(!s.intersects(&Segment::new(p(5, 5), p(6, 6)))); // same line, no overlap
+        assert!(!s.intersects(&Segment::new(p(1, 0), p(4, 3)))); // parallel
+        assert!(s.contains(p(3, 3)) && !s.contains(p(3, 2)));
+        assert_eq!(Segment::new(p(0.0, 0.0), p(3.0, 4.0)).length(), 5.0);
+    }
+
+    #[test]
+    fn circles() {
+        let c = Circle::new(p(0, 0), 5);
+        assert!(c.contains(p(3, 4)) && !c.contains(p(4, 4)));
+        assert!(c.intersects(&Circle::new(p(8, 0), 3)));
+        assert!(!c.intersects(&Circle::new(p(8, 1), 3)));
+        assert!(c.intersects_aabb(&Aabb::new(p(3, 4), p(10, 10))));
+        assert!(!c.intersects_aabb(&Aabb::new(p(4, 4), p(10, 10))));
+        assert_eq!(c.bounding_box(), Aabb::new(p(-5, -5), p(5, 5)));
+        assert!((Circle::new(p(1.0, 1.0), 2.0).area() - 4.0 * PI).abs() < 1e-12);
+        assert_eq!(c.perimeter(), 10.0 * PI);
+    }
+
+    #[test]
+    fn polygons() {
+        // an L shape, its notch at (2..4, 2..4) is outside
+        let l = Polygon::new(vec![p(0, 0), p(4, 0), p(4, 2), p(2, 2), p(2, 4), p(0, 4)]).unwrap();
+        assert_eq!(l.area(), 12.0);
+        assert_eq!(l.perimeter(), 16.0);
+        assert!(l.contains(p(1, 3)) && l.contains(p(3, 1)) && l.contains(p(2, 3)));
+        assert!(!l.contains(p(3, 3)) && !l.contains(p(5, 1)));
+        assert_eq!(l.bounding_box(), Aabb::new(p(0, 0), p(4, 4)));
+
+        // the same shape clockwise has the same area
+        let mut reversed = l.vertices().to_vec();
+        reversed.reverse();
+        let clockwise = Polygon::new(reversed).unwrap();
+        assert_eq!(clockwise.signed_double_area(), -l.signed_double_area());
+        assert_eq!(clockwise.area(), 12.0);
+
+        let triangle = Polygon::new(vec![p(0.0, 0.0), p(1.0, 0.0), p(0.0, 1.0)]).unwrap();
+        assert_eq!(triangle.area(), 0.5);
+        assert!(Polygon::new(vec![p(0, 0), p(1, 1)]).is_none());
+    }
+
+    #[test]
+    fn polygon_intersections() {
+        let square = |x, y, size| Polygon::new(vec![p(x, y), p(x + size, y), p(x + size, y + size), p(x, y + size)]).unwrap();
+        assert!(square(0, 0, 10).intersects(&square(2, 2, 2))); // inside
+        assert!(square(2, 2, 2).intersects(&square(0, 0, 10))); // around
+        assert!(square(0, 0, 4).intersects(&square(3, 3, 4))); // overlapping corners
+        assert!(!square(0, 0, 4).intersects(&square(5, 0, 4)));
+        assert!(square(0, 0, 4).intersects_segment(&Segment::new(p(-1, 2), p(9, 2))));
+        assert!(!square(0, 0, 4).intersects_segment(&Segment::new(p(-1, 5), p(9, 5))));
+    }
+}
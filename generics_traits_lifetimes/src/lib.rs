@@ -0,0 +1,24 @@
+// The types and traits that generics.rs, traits.rs and lifetimes.rs in src/bin show off,
+// grown into modules of the library crate so they can be tested and shared between the three,
+// eg: generics.rs uses generics_traits_lifetimes::geometry::Point instead of its own Point struct
+
+// the Tiger, Monkey and Eagle from src/bin/traits.rs and the traits they implement
+pub mod animals;
+
+// a seeded random number generator, used by the zoo and the tests
+pub mod rng;
+
+// a tick based simulation of a zoo, its enclosure rules come from the traits in animals
+pub mod zoo;
+
+// Point<T> and the numeric traits its arithmetic is bounded by
+pub mod geometry;
+
+// k-d tree and grid indexes for fast queries over many points
+pub mod spatial;
+
+// text utilities that borrow from their input, see src/bin/lifetimes.rs
+pub mod text;
+
+// largest, smallest, top_k... over any slice, the largest<T> from src/bin/generics.rs
+pub mod stats;
@@ -0,0 +1,176 @@
+// ------- Spatial indexes -------------
+// Finding the points near a place without looking at every point
+// kdtree.rs -> a k-d tree, splits the points in half by x, then by y, then by x...
+// grid.rs   -> a uniform grid, puts each point in a square cell of a fixed size
+// BruteForce below looks at every point, it is slow but obviously right,
+// the tests check the two indexes against it
+//
+// every index works with any Point<T> whose coordinates are numbers, distances are
+// measured in f64 so integer and unsigned coordinates work too (NaN coordinates don't)
+use crate::geometry::{Aabb, Num, Point, Shape};
+
+pub mod grid;
+pub mod kdtree;
+
+pub use grid::Grid;
+pub use kdtree::KdTree;
+
+pub trait SpatialIndex<T> {
+    fn insert(&mut self, point: Point<T>);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // every point inside the rectangle, edges included, in no particular order
+    fn in_rect(&self, rect: &Aabb<T>) -> Vec<Point<T>>;
+
+    // every point at most "radius" away from center, in no particular order
+    fn in_radius(&self, center: Point<T>, radius: f64) -> Vec<Point<T>>;
+
+    // the k points closest to "to", the closest first
+    // when several points are at the same distance any of them can be returned
+    fn nearest(&self, to: Point<T>, k: usize) -> Vec<Point<T>>;
+}
+
+// the squared distance in f64, so it can't overflow and works for unsigned coordinates
+pub fn distance_squared<T: Num>(a: Point<T>, b: Point<T>) -> f64 {
+    let dx = a.x.to_f64() - b.x.to_f64();
+    let dy = a.y.to_f64() - b.y.to_f64();
+    dx * dx + dy * dy
+}
+
+// the k closest points seen so far, kept sorted by distance, the furthest is last
+// k is small in practice, so a sorted Vec is as fast as a heap
+struct Nearest<T> {
+    k: usize,
+    found: Vec<(f64, Point<T>)>,
+}
+
+impl<T: Copy> Nearest<T> {
+    fn new(k: usize) -> Nearest<T> {
+        Nearest { k, found: Vec::with_capacity(k + 1) }
+    }
+
+    fn is_full(&self) -> bool {
+        self.found.len() == self.k
+    }
+
+    // the distance a point must beat to get in, infinite while there is room
+    fn worst(&self) -> f64 {
+        if self.is_full() {
+            self.found.last().map_or(f64::INFINITY, |(distance, _)| *distance)
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    fn offer(&mut self, distance: f64, point: Point<T>) {
+        if self.k == 0 || (self.is_full() && distance >= self.worst()) {
+            return;
+        }
+        let at = self.found.partition_point(|(d, _)| *d <= distance);
+        self.found.insert(at, (distance, point));
+        self.found.truncate(self.k);
+    }
+
+    fn into_points(self) -> Vec<Point<T>> {
+        self.found.into_iter().map(|(_, point)| point).collect()
+    }
+}
+
+// ------ Brute force ------
+#[derive(Debug, Clone, Default)]
+pub struct BruteForce<T> {
+    points: Vec<Point<T>>,
+}
+
+impl<T: Num> BruteForce<T> {
+    pub fn new(points: Vec<Point<T>>) -> BruteForce<T> {
+        BruteForce { points }
+    }
+}
+
+impl<T: Num> SpatialIndex<T> for BruteForce<T> {
+    fn insert(&mut self, point: Point<T>) {
+        self.points.push(point);
+    }
+
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn in_rect(&self, rect: &Aabb<T>) -> Vec<Point<T>> {
+        self.points.iter().copied().filter(|point| rect.contains(*point)).collect()
+    }
+
+    fn in_radius(&self, center: Point<T>, radius: f64) -> Vec<Point<T>> {
+        let limit = radius * radius;
+        self.points.iter().copied().filter(|point| distance_squared(center, *point) <= limit).collect()
+    }
+
+    fn nearest(&self, to: Point<T>, k: usize) -> Vec<Point<T>> {
+        let mut nearest = Nearest::new(k);
+        for point in &self.points {
+            nearest.offer(distance_squared(to, *point), *point);
+        }
+        nearest.into_points()
+    }
+}
+
+// helpers for the randomized tests of the indexes in grid.rs and kdtree.rs
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use std::cmp::Ordering;
+
+    pub(crate) fn sorted<T: Num>(mut points: Vec<Point<T>>) -> Vec<Point<T>> {
+        points.sort_by(|a, b| (a.x, a.y).partial_cmp(&(b.x, b.y)).unwrap_or(Ordering::Equal));
+        points
+    }
+
+    pub(crate) fn random_points(rng: &mut Rng, count: usize, size: f64) -> Vec<Point<f64>> {
+        (0..count).map(|_| Point::new(rng.next_f64() * size - size / 2.0, rng.next_f64() * size)).collect()
+    }
+
+    // integer points on a small board, so there are many duplicates and ties
+    pub(crate) fn random_int_points(rng: &mut Rng, count: usize) -> Vec<Point<i32>> {
+        (0..count).map(|_| Point::new(rng.below(40) as i32 - 20, rng.below(40) as i32)).collect()
+    }
+
+    // runs the same random queries on an index and on BruteForce
+    pub(crate) fn check_against_brute_force<T: Num, I: SpatialIndex<T>>(
+        index: &I,
+        brute: &BruteForce<T>,
+        queries: &[Point<T>],
+        radius: f64,
+    ) {
+        assert_eq!(index.len(), brute.len());
+        for (i, query) in queries.iter().enumerate() {
+            let corner = queries[(i + 1) % queries.len()];
+            let rect = Aabb::new(*query, corner);
+            assert_eq!(sorted(index.in_rect(&rect)), sorted(brute.in_rect(&rect)));
+            assert_eq!(sorted(index.in_radius(*query, radius)), sorted(brute.in_radius(*query, radius)));
+
+            // with ties the points can differ, their distances can't
+            for k in [0, 1, 7, brute.len() + 3] {
+                let distances = |points: Vec<Point<T>>| -> Vec<f64> {
+                    points.into_iter().map(|point| distance_squared(*query, point)).collect()
+                };
+                assert_eq!(distances(index.nearest(*query, k)), distances(brute.nearest(*query, k)));
+            }
+        }
+    }
+
+    #[test]
+    fn brute_force() {
+        let brute = BruteForce::new(vec![Point::new(0_u8, 0), Point::new(3, 4), Point::new(10, 0), Point::new(1, 1)]);
+        assert_eq!(brute.nearest(Point::new(0, 0), 2), [Point::new(0, 0), Point::new(1, 1)]);
+        assert_eq!(brute.in_radius(Point::new(0, 0), 5.0).len(), 3);
+        assert_eq!(brute.in_rect(&Aabb::new(Point::new(1, 1), Point::new(10, 4))).len(), 2);
+        assert!(BruteForce::<u8>::default().is_empty());
+    }
+}
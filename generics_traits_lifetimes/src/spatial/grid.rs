@@ -0,0 +1,230 @@
+// A uniform grid: the plane is cut into square cells of the same size and each point
+// is stored in the cell it falls in. a query only looks at the cells it overlaps,
+// so it is fastest when the cell size is close to the usual query radius
+//
+// only the cells that hold points are stored, in a HashMap keyed by the cell's column and row
+use std::collections::HashMap;
+
+use super::{distance_squared, Nearest, SpatialIndex};
+use crate::geometry::{Aabb, Num, Point, Shape};
+
+type Cell = (i64, i64);
+
+#[derive(Debug, Clone)]
+pub struct Grid<T> {
+    cell_size: f64,
+    cells: HashMap<Cell, Vec<Point<T>>>,
+    len: usize,
+    // the smallest box of cells holding every point, nearest() stops searching outside it
+    occupied: Option<(Cell, Cell)>,
+}
+
+impl<T: Num> Grid<T> {
+    // panics when cell_size is not a positive number
+    pub fn new(cell_size: f64) -> Grid<T> {
+        assert!(cell_size > 0.0 && cell_size.is_finite(), "the cell size must be positive, got {}", cell_size);
+        Grid { cell_size, cells: HashMap::new(), len: 0, occupied: None }
+    }
+
+    pub fn build(points: Vec<Point<T>>, cell_size: f64) -> Grid<T> {
+        let mut grid = Grid::new(cell_size);
+        for point in points {
+            grid.insert(point);
+        }
+        grid
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    fn cell_of(&self, x: f64, y: f64) -> Cell {
+        ((x / self.cell_size).floor() as i64, (y / self.cell_size).floor() as i64)
+    }
+
+    fn cell(&self, point: Point<T>) -> Cell {
+        self.cell_of(point.x.to_f64(), point.y.to_f64())
+    }
+
+    // calls found() for every point in the cells from "low" to "high"
+    // when the box covers more cells than are stored, it is faster to look at every stored cell
+    fn search(&self, low: Cell, high: Cell, mut found: impl FnMut(Point<T>)) {
+        let Some((first, last)) = self.occupied else { return };
+        let (low, high) = ((low.0.max(first.0), low.1.max(first.1)), (high.0.min(last.0), high.1.min(last.1)));
+        if low.0 > high.0 || low.1 > high.1 {
+            return;
+        }
+        let area = (high.0 - low.0 + 1).saturating_mul(high.1 - low.1 + 1);
+        if area as usize > self.cells.len() {
+            for (cell, points) in &self.cells {
+                if (low.0..=high.0).contains(&cell.0) && (low.1..=high.1).contains(&cell.1) {
+                    points.iter().for_each(|point| found(*point));
+                }
+            }
+        } else {
+            for column in low.0..=high.0 {
+                for row in low.1..=high.1 {
+                    self.cells.get(&(column, row)).into_iter().flatten().for_each(|point| found(*point));
+                }
+            }
+        }
+    }
+}
+
+impl<T: Num> SpatialIndex<T> for Grid<T> {
+    fn insert(&mut self, point: Point<T>) {
+        let cell = self.cell(point);
+        self.cells.entry(cell).or_default().push(point);
+        self.len += 1;
+        self.occupied = Some(match self.occupied {
+            None => (cell, cell),
+            Some((low, high)) => ((low.0.min(cell.0), low.1.min(cell.1)), (high.0.max(cell.0), high.1.max(cell.1))),
+        });
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn in_rect(&self, rect: &Aabb<T>) -> Vec<Point<T>> {
+        let mut points = Vec::new();
+        self.search(self.cell(rect.min), self.cell(rect.max), |point| {
+            if rect.contains(point) {
+                points.push(point);
+            }
+        });
+        points
+    }
+
+    fn in_radius(&self, center: Point<T>, radius: f64) -> Vec<Point<T>> {
+        let (x, y) = (center.x.to_f64(), center.y.to_f64());
+        let limit = radius * radius;
+        let mut points = Vec::new();
+        self.search(self.cell_of(x - radius, y - radius), self.cell_of(x + radius, y + radius), |point| {
+            if distance_squared(center, point) <= limit {
+                points.push(point);
+            }
+        });
+        points
+    }
+
+    // looks at rings of cells around the cell of "to": ring 0 is that cell, ring 1 the 8 cells
+    // around it and so on. every point outside ring r is more than r cells away, so once
+    // the k-th best is closer than that the search can stop.
+    // far from the points, or in a sparse grid, most rings are empty, so once more cells
+    // were looked at than are stored the rest of the stored cells are checked directly
+    fn nearest(&self, to: Point<T>, k: usize) -> Vec<Point<T>> {
+        let mut nearest = Nearest::new(k);
+        let Some((first, last)) = self.occupied else { return Vec::new() };
+        if k == 0 {
+            return Vec::new();
+        }
+        let center = self.cell(to);
+        // the rings before the first one touching an occupied cell are empty,
+        // after the last one every occupied cell has been looked at
+        let first_ring = [first.0 - center.0, center.0 - last.0, first.1 - center.1, center.1 - last.1, 0]
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        let last_ring = [center.0 - first.0, last.0 - center.0, center.1 - first.1, last.1 - center.1, 0]
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+
+        let mut looked_at = 0;
+        for ring in first_ring..=last_ring {
+            if looked_at > self.cells.len() {
+                for (cell, points) in &self.cells {
+                    if ring_of(center, *cell) >= ring {
+                        points.iter().for_each(|point| nearest.offer(distance_squared(to, *point), *point));
+                    }
+                }
+                break;
+            }
+            for cell in ring_cells(center, ring, first, last) {
+                looked_at += 1;
+                for point in self.cells.get(&cell).into_iter().flatten() {
+                    nearest.offer(distance_squared(to, *point), *point);
+                }
+            }
+            let reach = ring as f64 * self.cell_size;
+            if nearest.is_full() && nearest.worst() <= reach * reach {
+                break;
+            }
+        }
+        nearest.into_points()
+    }
+}
+
+// which ring around "center" a cell is on
+fn ring_of(center: Cell, cell: Cell) -> i64 {
+    (cell.0 - center.0).abs().max((cell.1 - center.1).abs())
+}
+
+// the cells on one ring around "center" that are inside the box from "first" to "last"
+fn ring_cells(center: Cell, ring: i64, first: Cell, last: Cell) -> Vec<Cell> {
+    let (low, high) = ((center.0 - ring, center.1 - ring), (center.0 + ring, center.1 + ring));
+    let columns = low.0.max(first.0)..=high.0.min(last.0);
+    let mut cells = Vec::new();
+    // the bottom and top rows, then the left and right columns between them
+    for row in [low.1, high.1] {
+        if (first.1..=last.1).contains(&row) {
+            cells.extend(columns.clone().map(|column| (column, row)));
+        }
+        if ring == 0 {
+            return cells;
+        }
+    }
+    for column in [low.0, high.0] {
+        if (first.0..=last.0).contains(&column) {
+            cells.extend(((low.1 + 1).max(first.1)..=(high.1 - 1).min(last.1)).map(|row| (column, row)));
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::spatial::tests::{check_against_brute_force, random_int_points, random_points};
+    use crate::spatial::BruteForce;
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = Rng::new(50);
+        for (count, cell_size) in [(0, 10.0), (1, 10.0), (40, 0.5), (3000, 25.0), (3000, 400.0)] {
+            let points = random_points(&mut rng, count, 1000.0);
+            // some queries are far outside the points
+            let queries = random_points(&mut rng, 20, 3000.0);
+            let grid = Grid::build(points.clone(), cell_size);
+            check_against_brute_force(&grid, &BruteForce::new(points), &queries, 60.0);
+        }
+    }
+
+    #[test]
+    fn integer_points_with_duplicates() {
+        let mut rng = Rng::new(51);
+        let points = random_int_points(&mut rng, 2000);
+        let queries = random_int_points(&mut rng, 30);
+        let grid = Grid::build(points.clone(), 4.0);
+        check_against_brute_force(&grid, &BruteForce::new(points), &queries, 3.5);
+    }
+
+    #[test]
+    fn ring_cells_are_the_border_of_a_square() {
+        let everywhere = ((-100, -100), (100, 100));
+        assert_eq!(ring_cells((0, 0), 0, everywhere.0, everywhere.1), [(0, 0)]);
+        let ring = ring_cells((5, 5), 2, everywhere.0, everywhere.1);
+        assert_eq!(ring.len(), 16);
+        assert!(ring.iter().all(|cell| ring_of((5, 5), *cell) == 2));
+        // clipped to the occupied cells
+        assert_eq!(ring_cells((0, 0), 1, (0, 0), (5, 5)), [(0, 1), (1, 1), (1, 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the cell size must be positive")]
+    fn rejects_a_zero_cell_size() {
+        Grid::<f64>::new(0.0);
+    }
+}
@@ -0,0 +1,221 @@
+// A 2-d tree: every node splits the plane in two with a line through its point,
+// the nodes at even depths split by x, the ones at odd depths by y.
+// a query only walks into the halves that can hold an answer
+//
+// the nodes are kept in a Vec and refer to their children by index, and the queries
+// walk the tree with their own stack, so a tree made unbalanced by inserts can't overflow the call stack
+use std::cmp::Ordering;
+
+use super::{distance_squared, Nearest, SpatialIndex};
+use crate::geometry::{Aabb, Num, Point, Shape};
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    point: Point<T>,
+    left: Option<usize>,  // coordinates less or equal to the split
+    right: Option<usize>, // coordinates greater or equal to the split
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KdTree<T> {
+    nodes: Vec<Node<T>>,
+    root: Option<usize>,
+}
+
+// axis 0 is x, 1 is y
+fn coord<T: Copy>(point: &Point<T>, axis: usize) -> T {
+    if axis == 0 {
+        point.x
+    } else {
+        point.y
+    }
+}
+
+fn compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+impl<T: Num> KdTree<T> {
+    pub fn new() -> KdTree<T> {
+        KdTree { nodes: Vec::new(), root: None }
+    }
+
+    // a balanced tree: each node is the median of its points on its axis
+    pub fn build(mut points: Vec<Point<T>>) -> KdTree<T> {
+        let mut tree = KdTree { nodes: Vec::with_capacity(points.len()), root: None };
+        tree.root = tree.build_node(&mut points, 0);
+        tree
+    }
+
+    // the depth of a balanced tree is log2 of the number of points, so recursion is fine here
+    fn build_node(&mut self, points: &mut [Point<T>], depth: usize) -> Option<usize> {
+        if points.is_empty() {
+            return None;
+        }
+        let axis = depth % 2;
+        let middle = points.len() / 2;
+        points.select_nth_unstable_by(middle, |a, b| compare(&coord(a, axis), &coord(b, axis)));
+
+        let index = self.nodes.len();
+        self.nodes.push(Node { point: points[middle], left: None, right: None });
+        let (left, right) = points.split_at_mut(middle);
+        self.nodes[index].left = self.build_node(left, depth + 1);
+        self.nodes[index].right = self.build_node(&mut right[1..], depth + 1);
+        Some(index)
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point<T>> {
+        self.nodes.iter().map(|node| &node.point)
+    }
+
+    // visits the nodes that may be inside the box from "low" to "high", calls found() for each
+    fn search(&self, low: [f64; 2], high: [f64; 2], mut found: impl FnMut(Point<T>)) {
+        let mut stack: Vec<(usize, usize)> = self.root.map(|root| (root, 0)).into_iter().collect();
+        while let Some((index, depth)) = stack.pop() {
+            let node = &self.nodes[index];
+            found(node.point);
+            let axis = depth % 2;
+            let split = coord(&node.point, axis).to_f64();
+            if let Some(left) = node.left.filter(|_| low[axis] <= split) {
+                stack.push((left, depth + 1));
+            }
+            if let Some(right) = node.right.filter(|_| high[axis] >= split) {
+                stack.push((right, depth + 1));
+            }
+        }
+    }
+}
+
+impl<T: Num> SpatialIndex<T> for KdTree<T> {
+    // walks down to an empty child, equal coordinates go right
+    fn insert(&mut self, point: Point<T>) {
+        let index = self.nodes.len();
+        self.nodes.push(Node { point, left: None, right: None });
+        let mut current = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(index);
+                return;
+            }
+        };
+        let mut depth = 0;
+        loop {
+            let axis = depth % 2;
+            let node = &mut self.nodes[current];
+            let child = if coord(&point, axis) < coord(&node.point, axis) { &mut node.left } else { &mut node.right };
+            match child {
+                Some(next) => current = *next,
+                None => {
+                    *child = Some(index);
+                    return;
+                }
+            }
+            depth += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn in_rect(&self, rect: &Aabb<T>) -> Vec<Point<T>> {
+        let low = [rect.min.x.to_f64(), rect.min.y.to_f64()];
+        let high = [rect.max.x.to_f64(), rect.max.y.to_f64()];
+        let mut points = Vec::new();
+        self.search(low, high, |point| {
+            if rect.contains(point) {
+                points.push(point);
+            }
+        });
+        points
+    }
+
+    fn in_radius(&self, center: Point<T>, radius: f64) -> Vec<Point<T>> {
+        let (x, y) = (center.x.to_f64(), center.y.to_f64());
+        let limit = radius * radius;
+        let mut points = Vec::new();
+        self.search([x - radius, y - radius], [x + radius, y + radius], |point| {
+            if distance_squared(center, point) <= limit {
+                points.push(point);
+            }
+        });
+        points
+    }
+
+    // depth first, the half with "to" in it first, each stack entry has the smallest
+    // distance its half can be from "to", halves further than the k-th best are skipped
+    fn nearest(&self, to: Point<T>, k: usize) -> Vec<Point<T>> {
+        let mut nearest = Nearest::new(k);
+        let mut stack: Vec<(usize, usize, f64)> = self.root.map(|root| (root, 0, 0.0)).into_iter().collect();
+        while let Some((index, depth, bound)) = stack.pop() {
+            if k == 0 || bound > nearest.worst() {
+                continue;
+            }
+            let node = &self.nodes[index];
+            nearest.offer(distance_squared(to, node.point), node.point);
+
+            let axis = depth % 2;
+            let gap = coord(&to, axis).to_f64() - coord(&node.point, axis).to_f64();
+            let (near, far) = if gap < 0.0 { (node.left, node.right) } else { (node.right, node.left) };
+            // the far half is pushed first so the near half is searched first
+            if let Some(far) = far {
+                stack.push((far, depth + 1, bound.max(gap * gap)));
+            }
+            if let Some(near) = near {
+                stack.push((near, depth + 1, bound));
+            }
+        }
+        nearest.into_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::spatial::tests::{check_against_brute_force, random_int_points, random_points, sorted};
+    use crate::spatial::BruteForce;
+
+    #[test]
+    fn built_and_inserted_trees_match_brute_force() {
+        let mut rng = Rng::new(40);
+        for count in [0, 1, 2, 50, 3000] {
+            let points = random_points(&mut rng, count, 1000.0);
+            let queries = random_points(&mut rng, 20, 1200.0);
+            let brute = BruteForce::new(points.clone());
+            check_against_brute_force(&KdTree::build(points.clone()), &brute, &queries, 60.0);
+
+            let mut inserted = KdTree::new();
+            for point in &points {
+                inserted.insert(*point);
+            }
+            check_against_brute_force(&inserted, &brute, &queries, 60.0);
+        }
+    }
+
+    #[test]
+    fn integer_points_with_duplicates() {
+        let mut rng = Rng::new(41);
+        let points = random_int_points(&mut rng, 2000);
+        let queries = random_int_points(&mut rng, 30);
+        let brute = BruteForce::new(points.clone());
+
+        // half built, half inserted, so both kinds of nodes hold equal coordinates
+        let mut tree = KdTree::build(points[..1000].to_vec());
+        for point in &points[1000..] {
+            tree.insert(*point);
+        }
+        check_against_brute_force(&tree, &brute, &queries, 3.5);
+        assert_eq!(sorted(tree.points().copied().collect()), sorted(points));
+    }
+
+    #[test]
+    fn sorted_inserts_do_not_overflow_the_stack() {
+        let mut tree = KdTree::new();
+        for i in 0..10_000_u32 {
+            tree.insert(Point::new(i, i));
+        }
+        assert_eq!(tree.nearest(Point::new(9_999, 9_999), 2), [Point::new(9_999, 9_999), Point::new(9_998, 9_998)]);
+        assert_eq!(tree.in_radius(Point::new(0, 0), 1.5).len(), 2);
+    }
+}
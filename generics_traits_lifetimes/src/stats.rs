@@ -0,0 +1,236 @@
+// Generic statistics over slices: fn largest<T>(list: &[T]) -> Option<&T> and friends
+//
+// comparing needs an order, but f32 and f64 are only PartialOrd: NaN is not smaller,
+// equal or larger than anything. TotalOrd gives every type here a total order,
+// for floats it is the IEEE total order: -NaN < -inf < ... < -0.0 < 0.0 < ... < inf < NaN
+//
+// every function returns None for an empty slice, on ties the first one wins,
+// and all of them look at each element once, except the top_k functions which have to select
+use std::cmp::Ordering;
+
+pub trait TotalOrd {
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_total_ord {
+    ($($t:ty),*) => {
+        $(
+            impl TotalOrd for $t {
+                fn total_cmp(&self, other: &$t) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_total_ord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, bool, char, str, String);
+
+impl TotalOrd for f32 {
+    fn total_cmp(&self, other: &f32) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+impl TotalOrd for f64 {
+    fn total_cmp(&self, other: &f64) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+}
+
+impl<T: TotalOrd + ?Sized> TotalOrd for &T {
+    fn total_cmp(&self, other: &&T) -> Ordering {
+        (**self).total_cmp(*other)
+    }
+}
+
+// ------ With a comparison function ------
+// the other functions are these with a comparison built from TotalOrd
+
+pub fn argmax_by<T>(list: &[T], mut compare: impl FnMut(&T, &T) -> Ordering) -> Option<usize> {
+    let mut best = 0;
+    for index in 1..list.len() {
+        if compare(&list[index], &list[best]) == Ordering::Greater {
+            best = index;
+        }
+    }
+    (!list.is_empty()).then_some(best)
+}
+
+pub fn argmin_by<T>(list: &[T], mut compare: impl FnMut(&T, &T) -> Ordering) -> Option<usize> {
+    argmax_by(list, |a, b| compare(b, a))
+}
+
+pub fn largest_by<T>(list: &[T], compare: impl FnMut(&T, &T) -> Ordering) -> Option<&T> {
+    argmax_by(list, compare).map(|index| &list[index])
+}
+
+pub fn smallest_by<T>(list: &[T], compare: impl FnMut(&T, &T) -> Ordering) -> Option<&T> {
+    argmin_by(list, compare).map(|index| &list[index])
+}
+
+// the smallest and the largest in one pass
+pub fn min_max_by<T>(list: &[T], mut compare: impl FnMut(&T, &T) -> Ordering) -> Option<(&T, &T)> {
+    let (first, rest) = list.split_first()?;
+    let (mut min, mut max) = (first, first);
+    for item in rest {
+        if compare(item, min) == Ordering::Less {
+            min = item;
+        } else if compare(item, max) == Ordering::Greater {
+            max = item;
+        }
+    }
+    Some((min, max))
+}
+
+// the k largest, the largest first, equal ones in the order they are in the list
+pub fn top_k_by<T>(list: &[T], k: usize, mut compare: impl FnMut(&T, &T) -> Ordering) -> Vec<&T> {
+    // largest first, and the earlier index first among equals
+    let mut descending = |a: &usize, b: &usize| compare(&list[*b], &list[*a]).then(a.cmp(b));
+    let mut indexes: Vec<usize> = (0..list.len()).collect();
+    if k == 0 {
+        return Vec::new();
+    }
+    if k < indexes.len() {
+        indexes.select_nth_unstable_by(k - 1, &mut descending);
+        indexes.truncate(k);
+    }
+    indexes.sort_unstable_by(&mut descending);
+    indexes.into_iter().map(|index| &list[index]).collect()
+}
+
+// ------ With TotalOrd ------
+
+pub fn largest<T: TotalOrd>(list: &[T]) -> Option<&T> {
+    largest_by(list, T::total_cmp)
+}
+
+pub fn smallest<T: TotalOrd>(list: &[T]) -> Option<&T> {
+    smallest_by(list, T::total_cmp)
+}
+
+pub fn min_max<T: TotalOrd>(list: &[T]) -> Option<(&T, &T)> {
+    min_max_by(list, T::total_cmp)
+}
+
+pub fn argmax<T: TotalOrd>(list: &[T]) -> Option<usize> {
+    argmax_by(list, T::total_cmp)
+}
+
+pub fn argmin<T: TotalOrd>(list: &[T]) -> Option<usize> {
+    argmin_by(list, T::total_cmp)
+}
+
+pub fn top_k<T: TotalOrd>(list: &[T], k: usize) -> Vec<&T> {
+    top_k_by(list, k, T::total_cmp)
+}
+
+// ------ With a key ------
+// eg: largest_by_key(&animals, |animal| animal.legs)
+
+pub fn largest_by_key<T, K: TotalOrd>(list: &[T], mut key: impl FnMut(&T) -> K) -> Option<&T> {
+    largest_by(list, |a, b| key(a).total_cmp(&key(b)))
+}
+
+pub fn smallest_by_key<T, K: TotalOrd>(list: &[T], mut key: impl FnMut(&T) -> K) -> Option<&T> {
+    smallest_by(list, |a, b| key(a).total_cmp(&key(b)))
+}
+
+pub fn argmax_by_key<T, K: TotalOrd>(list: &[T], mut key: impl FnMut(&T) -> K) -> Option<usize> {
+    argmax_by(list, |a, b| key(a).total_cmp(&key(b)))
+}
+
+pub fn top_k_by_key<T, K: TotalOrd>(list: &[T], k: usize, mut key: impl FnMut(&T) -> K) -> Vec<&T> {
+    top_k_by(list, k, |a, b| key(a).total_cmp(&key(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    // the references sort everything, slow but easy to trust
+    // a stable sort keeps equal elements in list order, so ties are resolved like above
+    fn sorted_indexes<T: TotalOrd>(list: &[T]) -> Vec<usize> {
+        let mut indexes: Vec<usize> = (0..list.len()).collect();
+        indexes.sort_by(|a, b| list[*b].total_cmp(&list[*a]));
+        indexes
+    }
+
+    fn check<T: TotalOrd + std::fmt::Debug>(list: &[T]) {
+        let descending = sorted_indexes(list);
+        let first_of = |wanted: &T| list.iter().position(|item| item.total_cmp(wanted) == Ordering::Equal);
+
+        assert_eq!(argmax(list), descending.first().copied());
+        assert_eq!(largest(list).map(|item| item as *const T), descending.first().map(|i| &list[*i] as *const T));
+        let min = descending.last().map(|i| &list[*i]);
+        assert_eq!(argmin(list), min.and_then(first_of));
+        assert_eq!(smallest(list).map(|item| item as *const T), argmin(list).map(|i| &list[i] as *const T));
+        assert_eq!(
+            min_max(list).map(|(a, b)| (a as *const T, b as *const T)),
+            argmin(list).zip(argmax(list)).map(|(a, b)| (&list[a] as *const T, &list[b] as *const T))
+        );
+        for k in [0, 1, 3, list.len() / 2, list.len() + 2] {
+            let expected: Vec<*const T> = descending.iter().take(k).map(|i| &list[*i] as *const T).collect();
+            let found: Vec<*const T> = top_k(list, k).into_iter().map(|item| item as *const T).collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn matches_sorting_on_random_integers() {
+        let mut rng = Rng::new(42);
+        for length in 0..60 {
+            // a small range so there are many ties
+            let list: Vec<i32> = (0..length).map(|_| rng.below(10) as i32 - 5).collect();
+            check(&list);
+        }
+    }
+
+    #[test]
+    fn matches_sorting_on_random_floats() {
+        let special = [f64::NAN, -f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 0.0, -0.0];
+        let mut rng = Rng::new(7);
+        for length in 0..60 {
+            let list: Vec<f64> = (0..length)
+                .map(|_| match rng.below(4) {
+                    0 => special[rng.below(special.len() as u64) as usize],
+                    _ => (rng.next_f64() * 20.0).round() - 10.0,
+                })
+                .collect();
+            check(&list);
+        }
+    }
+
+    #[test]
+    fn floats_use_the_total_order() {
+        let list = [1.0, f64::NAN, -0.0, 0.0, f64::NEG_INFINITY];
+        assert!(largest(&list).unwrap().is_nan());
+        assert_eq!(argmin(&list), Some(4));
+        // -0.0 comes before 0.0
+        assert_eq!(top_k(&[0.0, -0.0, 2.0], 3).iter().map(|x| x.to_string()).collect::<Vec<_>>(), ["2", "0", "-0"]);
+    }
+
+    #[test]
+    fn empty_lists_have_no_answer() {
+        let empty: [u8; 0] = [];
+        assert_eq!(largest(&empty), None);
+        assert_eq!(min_max(&empty), None);
+        assert_eq!(argmax(&empty), None);
+        assert!(top_k(&empty, 3).is_empty());
+    }
+
+    #[test]
+    fn key_variants_keep_the_first_on_ties() {
+        let animals = [("Tiger", 4), ("Eagle", 2), ("Monkey", 2), ("Bear", 4), ("Snake", 0)];
+        assert_eq!(largest_by_key(&animals, |animal| animal.1), Some(&("Tiger", 4)));
+        assert_eq!(smallest_by_key(&animals, |animal| animal.1), Some(&("Snake", 0)));
+        assert_eq!(argmax_by_key(&animals, |animal| animal.0.len()), Some(2));
+        assert_eq!(
+            top_k_by_key(&animals, 3, |animal| animal.1),
+            [&("Tiger", 4), &("Bear", 4), &("Eagle", 2)]
+        );
+        assert_eq!(largest(&["tiger", "eagle"]), Some(&"tiger"));
+        assert_eq!(largest_by(&animals, |a, b| a.0.cmp(b.0)), Some(&("Tiger", 4)));
+    }
+}
@@ -0,0 +1,354 @@
+// Zero-copy text utilities: everything here returns slices of the input instead of new Strings
+//
+// a lifetime parameter like 'a names "how long the input lives", a function
+// fn f<'a>(input: &'a str) -> &'a str tells the compiler the result points into the input,
+// so the result can't be used after the input is dropped. the owned types at the bottom
+// copy the text out when a result has to outlive its input
+use std::fmt;
+
+/// Returns the longer of two strings, the first one when they are the same length.
+///
+/// Both inputs have the lifetime `'a`, so the result can only be used while both live:
+///
+/// ```compile_fail
+/// use generics_traits_lifetimes::text::longest;
+/// let outer = String::from("a long string");
+/// let result;
+/// {
+///     let inner = String::from("short");
+///     result = longest(&outer, &inner);
+/// }
+/// println!("{}", result); // inner was dropped, result might point into it
+/// ```
+pub fn longest<'a>(a: &'a str, b: &'a str) -> &'a str {
+    if b.len() > a.len() {
+        b
+    } else {
+        a
+    }
+}
+
+// ------ Fields ------
+// the fields of one line, eg: "Tiger, 4 ,  mammal" split on ',' gives "Tiger", "4", "mammal"
+// the surrounding spaces are trimmed, an empty line still has one (empty) field
+#[derive(Debug, Clone)]
+pub struct Fields<'a> {
+    rest: Option<&'a str>,
+    separator: char,
+}
+
+pub fn split_fields(line: &str, separator: char) -> Fields<'_> {
+    Fields { rest: Some(line), separator }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest?;
+        match rest.split_once(self.separator) {
+            Some((field, after)) => {
+                self.rest = Some(after);
+                Some(field.trim())
+            }
+            None => {
+                self.rest = None;
+                Some(rest.trim())
+            }
+        }
+    }
+}
+
+// ------ Tokenizer ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,   // letters, digits and _, starting with a letter or _
+    Number, // digits, with an optional fraction: 42, 3.14
+    Quoted, // "a string", the text is what is between the quotes
+    Symbol, // any other single character
+}
+
+// a token borrows its text from the source, offset is where it starts (in bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnterminatedQuote {
+    pub offset: usize,
+}
+
+impl fmt::Display for UnterminatedQuote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the quote at byte {} is never closed", self.offset)
+    }
+}
+
+impl std::error::Error for UnterminatedQuote {}
+
+// splits text into tokens, skipping whitespace, an iterator of Result<Token, UnterminatedQuote>
+// after an error it returns None
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+pub fn tokenize(source: &str) -> Tokenizer<'_> {
+    Tokenizer { source, position: 0 }
+}
+
+impl<'a> Tokenizer<'a> {
+    // the length of the start of "text" whose chars match
+    fn run(text: &str, matches: impl Fn(char) -> bool) -> usize {
+        text.find(|c| !matches(c)).unwrap_or(text.len())
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, UnterminatedQuote>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.source[self.position..];
+        let skipped = Tokenizer::run(rest, char::is_whitespace);
+        let offset = self.position + skipped;
+        let rest = &rest[skipped..];
+        let first = rest.chars().next()?;
+
+        let (kind, start, len, end) = if first == '"' {
+            match rest[1..].find('"') {
+                Some(close) => (TokenKind::Quoted, 1, close, close + 2),
+                None => {
+                    self.position = self.source.len();
+                    return Some(Err(UnterminatedQuote { offset }));
+                }
+            }
+        } else if first.is_ascii_digit() {
+            let mut len = Tokenizer::run(rest, |c| c.is_ascii_digit());
+            let fraction = &rest[len..];
+            if fraction.starts_with('.') && fraction[1..].starts_with(|c: char| c.is_ascii_digit()) {
+                len += 1 + Tokenizer::run(&fraction[1..], |c| c.is_ascii_digit());
+            }
+            (TokenKind::Number, 0, len, len)
+        } else if first.is_alphabetic() || first == '_' {
+            let len = Tokenizer::run(rest, |c| c.is_alphanumeric() || c == '_');
+            (TokenKind::Word, 0, len, len)
+        } else {
+            (TokenKind::Symbol, 0, first.len_utf8(), first.len_utf8())
+        };
+
+        self.position = offset + end;
+        Some(Ok(Token { kind, text: &rest[start..start + len], offset: offset + start }))
+    }
+}
+
+// ------ Key value view ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize, // starts at 1
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A `key = value` per line view of some text, the keys and values are slices of it.
+/// Blank lines and lines starting with `#` are skipped.
+///
+/// `get()` returns a `&'a str`, tied to the text and not to the view, so a value
+/// can outlive the view it came from:
+///
+/// ```
+/// use generics_traits_lifetimes::text::KeyValueView;
+/// let text = String::from("name = Tiger\nlegs = 4");
+/// let name = {
+///     let view = KeyValueView::parse(&text).unwrap();
+///     view.get("name").unwrap()
+/// };
+/// assert_eq!(name, "Tiger");
+/// ```
+///
+/// but neither can outlive the text:
+///
+/// ```compile_fail
+/// use generics_traits_lifetimes::text::KeyValueView;
+/// let view;
+/// {
+///     let text = String::from("name = Tiger");
+///     view = KeyValueView::parse(&text).unwrap();
+/// }
+/// println!("{:?}", view.get("name"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyValueView<'a> {
+    pairs: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> KeyValueView<'a> {
+    pub fn parse(text: &'a str) -> Result<KeyValueView<'a>, ParseError> {
+        let mut pairs = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message| ParseError { line: index + 1, message };
+            let (key, value) = line.split_once('=').ok_or(error("expected key = value"))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(error("empty key"));
+            }
+            pairs.push((key, value.trim()));
+        }
+        Ok(KeyValueView { pairs })
+    }
+
+    // the last value wins when a key is repeated
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.pairs.iter().rev().find(|(k, _)| *k == key).map(|(_, value)| *value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.pairs.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn to_owned_pairs(&self) -> OwnedKeyValues {
+        OwnedKeyValues::from(self)
+    }
+}
+
+// ------ Owned counterparts ------
+// these copy the text, they have no lifetime parameter and can be kept as long as needed
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedToken {
+    pub kind: TokenKind,
+    pub text: String,
+    pub offset: usize,
+}
+
+impl From<Token<'_>> for OwnedToken {
+    fn from(token: Token<'_>) -> OwnedToken {
+        OwnedToken { kind: token.kind, text: token.text.to_string(), offset: token.offset }
+    }
+}
+
+impl OwnedToken {
+    // a token borrowing from this one, it can't outlive it
+    pub fn as_token(&self) -> Token<'_> {
+        Token { kind: self.kind, text: &self.text, offset: self.offset }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OwnedKeyValues {
+    pairs: Vec<(String, String)>,
+}
+
+impl From<&KeyValueView<'_>> for OwnedKeyValues {
+    fn from(view: &KeyValueView<'_>) -> OwnedKeyValues {
+        OwnedKeyValues { pairs: view.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect() }
+    }
+}
+
+impl OwnedKeyValues {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.view().get(key)
+    }
+
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.pairs.push((key.to_string(), value.to_string()));
+    }
+
+    // borrows the owned strings, the view lives as long as the borrow of self
+    pub fn view(&self) -> KeyValueView<'_> {
+        KeyValueView { pairs: self.pairs.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_keeps_the_first_on_ties() {
+        assert_eq!(longest("tiger", "eagle"), "tiger");
+        assert_eq!(longest("ape", "monkey"), "monkey");
+    }
+
+    #[test]
+    fn fields_are_slices_of_the_line() {
+        let line = String::from(" Tiger, 4 ,, mammal ");
+        let fields: Vec<&str> = split_fields(&line, ',').collect();
+        assert_eq!(fields, ["Tiger", "4", "", "mammal"]);
+        // the same memory as the line, nothing was copied
+        assert!(line.as_bytes().as_ptr_range().contains(&fields[0].as_ptr()));
+        assert_eq!(split_fields("", ';').collect::<Vec<_>>(), [""]);
+    }
+
+    #[test]
+    fn tokens() {
+        let source = "let name = \"Shere Khan\"; legs=4.0 + x_1 .5 ü";
+        let tokens: Vec<(TokenKind, &str)> = tokenize(source).map(|t| t.map(|t| (t.kind, t.text)).unwrap()).collect();
+        use TokenKind::*;
+        assert_eq!(
+            tokens,
+            [
+                (Word, "let"), (Word, "name"), (Symbol, "="), (Quoted, "Shere Khan"), (Symbol, ";"), (Word, "legs"),
+                (Symbol, "="), (Number, "4.0"), (Symbol, "+"), (Word, "x_1"), (Symbol, "."), (Number, "5"), (Word, "ü"),
+            ]
+        );
+        let quoted = tokenize(source).nth(3).unwrap().unwrap();
+        assert_eq!(&source[quoted.offset..quoted.offset + quoted.text.len()], "Shere Khan");
+
+        let mut broken = tokenize("ok \"open");
+        assert_eq!(broken.next().unwrap().unwrap().text, "ok");
+        assert_eq!(broken.next(), Some(Err(UnterminatedQuote { offset: 3 })));
+        assert_eq!(broken.next(), None);
+    }
+
+    #[test]
+    fn key_values() {
+        let text = "# an animal\nname = Tiger\n\nlegs=4\nname = Shere Khan\n";
+        let view = KeyValueView::parse(text).unwrap();
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.get("name"), Some("Shere Khan"));
+        assert_eq!(view.get("legs"), Some("4"));
+        assert_eq!(view.get("wings"), None);
+        assert_eq!(KeyValueView::parse("a = 1\nnope"), Err(ParseError { line: 2, message: "expected key = value" }));
+        assert_eq!(KeyValueView::parse(" = 1").unwrap_err().message, "empty key");
+    }
+
+    #[test]
+    fn owned_copies_outlive_the_source() {
+        let (owned, token) = {
+            let text = String::from("legs = 4");
+            let view = KeyValueView::parse(&text).unwrap();
+            let token = OwnedToken::from(tokenize(&text).next().unwrap().unwrap());
+            (view.to_owned_pairs(), token)
+        };
+        assert_eq!(owned.get("legs"), Some("4"));
+        assert_eq!(token.as_token(), Token { kind: TokenKind::Word, text: "legs", offset: 0 });
+
+        let mut owned = owned;
+        owned.insert("legs", "3");
+        assert_eq!(owned.view().iter().collect::<Vec<_>>(), [("legs", "4"), ("legs", "3")]);
+        assert_eq!(owned.get("legs"), Some("3"));
+    }
+}
@@ -0,0 +1,331 @@
+// A tick based zoo simulation over the animals in src/animals.rs
+//
+// - an Enclosure has a capacity, a roof or not, and a feeding schedule
+// - who may live together is decided from the animal traits: diet, species and can_fly()
+// - every tick each animal gets hungrier, a hungry animal loses health, a fed one recovers
+// - illness is random, the random numbers come from the zoo's seed so a run can be repeated
+// - everything that happens is written to an event log, tests assert on it
+use std::fmt;
+
+use crate::animals::{can_fly, Animal, Diet};
+use crate::rng::Rng;
+
+// hunger and health go from 0 to 100
+pub const MAX_LEVEL: u8 = 100;
+// above this hunger an animal starts to lose health
+pub const STARVING: u8 = 70;
+// one chance in ILLNESS_ODDS, every tick, for every animal to fall ill
+const ILLNESS_ODDS: u64 = 250;
+const ILLNESS_DAMAGE: u8 = 30;
+
+// ------ Enclosures ------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedingSchedule {
+    pub first_tick: u32,
+    pub every: u32, // 0 means the enclosure is only fed once, on first_tick
+}
+
+impl FeedingSchedule {
+    pub fn is_feeding_time(&self, tick: u32) -> bool {
+        match (tick.checked_sub(self.first_tick), self.every) {
+            (None, _) => false,
+            (Some(since), 0) => since == 0,
+            (Some(since), every) => since % every == 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AnimalId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EnclosureId(pub usize);
+
+#[derive(Debug)]
+pub struct Resident {
+    pub id: AnimalId,
+    pub name: String,
+    pub animal: Box<dyn Animal>,
+    pub hunger: u8,
+    pub health: u8,
+}
+
+#[derive(Debug)]
+pub struct Enclosure {
+    pub name: String,
+    pub capacity: usize,
+    pub roofed: bool,
+    pub schedule: FeedingSchedule,
+    residents: Vec<Resident>,
+}
+
+impl Enclosure {
+    pub fn new(name: &str, capacity: usize, roofed: bool, schedule: FeedingSchedule) -> Enclosure {
+        Enclosure { name: name.to_string(), capacity, roofed, schedule, residents: Vec::new() }
+    }
+
+    pub fn residents(&self) -> &[Resident] {
+        &self.residents
+    }
+
+    // the compatibility rules, checked before an animal moves in
+    pub fn check(&self, animal: &dyn Animal) -> Result<(), AdmitError> {
+        if self.residents.len() >= self.capacity {
+            return Err(AdmitError::Full);
+        }
+        if animal.as_bird().is_some_and(can_fly) && !self.roofed {
+            return Err(AdmitError::NeedsRoof);
+        }
+        // a carnivore eats any other species it lives with, and is eaten by other carnivores
+        let clash = self.residents.iter().find(|resident| {
+            let other = resident.animal.as_ref();
+            other.species() != animal.species() && (other.diet() == Diet::Carnivore || animal.diet() == Diet::Carnivore)
+        });
+        match clash {
+            Some(resident) => Err(AdmitError::Incompatible { with: resident.animal.species() }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdmitError {
+    UnknownEnclosure,
+    Full,
+    NeedsRoof, // a bird that can fly would escape
+    Incompatible { with: &'static str },
+}
+
+impl fmt::Display for AdmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdmitError::UnknownEnclosure => write!(f, "there is no such enclosure"),
+            AdmitError::Full => write!(f, "the enclosure is full"),
+            AdmitError::NeedsRoof => write!(f, "a flying bird needs a roofed enclosure"),
+            AdmitError::Incompatible { with } => write!(f, "can't live with the {}", with),
+        }
+    }
+}
+
+impl std::error::Error for AdmitError {}
+
+// ------ Events ------
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    Admitted { animal: AnimalId, enclosure: EnclosureId },
+    Fed { enclosure: EnclosureId, animals: usize },
+    Starving { animal: AnimalId },
+    FellIll { animal: AnimalId },
+    Died { animal: AnimalId },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub tick: u32,
+    pub kind: EventKind,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tick {}: ", self.tick)?;
+        match &self.kind {
+            EventKind::Admitted { animal, enclosure } => write!(f, "animal {} admitted to enclosure {}", animal.0, enclosure.0),
+            EventKind::Fed { enclosure, animals } => write!(f, "fed {} animal(s) in enclosure {}", animals, enclosure.0),
+            EventKind::Starving { animal } => write!(f, "animal {} is starving", animal.0),
+            EventKind::FellIll { animal } => write!(f, "animal {} fell ill", animal.0),
+            EventKind::Died { animal } => write!(f, "animal {} died", animal.0),
+        }
+    }
+}
+
+// ------ The zoo ------
+#[derive(Debug)]
+pub struct Zoo {
+    rng: Rng,
+    tick: u32,
+    next_id: u32,
+    enclosures: Vec<Enclosure>,
+    log: Vec<Event>,
+}
+
+// how much hungrier an animal can get in one tick, meat eaters get hungry faster
+fn appetite(animal: &dyn Animal) -> u8 {
+    match animal.diet() {
+        Diet::Carnivore => 4,
+        Diet::Omnivore | Diet::Piscivore => 3,
+    }
+}
+
+impl Zoo {
+    pub fn new(seed: u64) -> Zoo {
+        Zoo { rng: Rng::new(seed), tick: 0, next_id: 1, enclosures: Vec::new(), log: Vec::new() }
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn log(&self) -> &[Event] {
+        &self.log
+    }
+
+    pub fn add_enclosure(&mut self, enclosure: Enclosure) -> EnclosureId {
+        self.enclosures.push(enclosure);
+        EnclosureId(self.enclosures.len() - 1)
+    }
+
+    pub fn enclosure(&self, id: EnclosureId) -> Option<&Enclosure> {
+        self.enclosures.get(id.0)
+    }
+
+    pub fn resident(&self, id: AnimalId) -> Option<&Resident> {
+        self.enclosures.iter().flat_map(|enclosure| &enclosure.residents).find(|resident| resident.id == id)
+    }
+
+    // new animals arrive fed and healthy
+    pub fn admit(&mut self, to: EnclosureId, name: &str, animal: Box<dyn Animal>) -> Result<AnimalId, AdmitError> {
+        let enclosure = self.enclosures.get_mut(to.0).ok_or(AdmitError::UnknownEnclosure)?;
+        enclosure.check(animal.as_ref())?;
+        let id = AnimalId(self.next_id);
+        self.next_id += 1;
+        enclosure.residents.push(Resident { id, name: name.to_string(), animal, hunger: 0, health: MAX_LEVEL });
+        self.log.push(Event { tick: self.tick, kind: EventKind::Admitted { animal: id, enclosure: to } });
+        Ok(id)
+    }
+
+    // one tick: feed the enclosures on schedule, then every animal gets hungrier,
+    // gains or loses health and may fall ill, animals with no health left die
+    pub fn step(&mut self) {
+        self.tick += 1;
+        let tick = self.tick;
+
+        for (index, enclosure) in self.enclosures.iter_mut().enumerate() {
+            if enclosure.schedule.is_feeding_time(tick) && !enclosure.residents.is_empty() {
+                for resident in &mut enclosure.residents {
+                    resident.hunger = 0;
+                }
+                let animals = enclosure.residents.len();
+                self.log.push(Event { tick, kind: EventKind::Fed { enclosure: EnclosureId(index), animals } });
+            }
+
+            for resident in &mut enclosure.residents {
+                let was_starving = resident.hunger >= STARVING;
+                let gain = 1 + self.rng.below(appetite(resident.animal.as_ref()) as u64) as u8;
+                resident.hunger = resident.hunger.saturating_add(gain).min(MAX_LEVEL);
+                if resident.hunger >= STARVING {
+                    if !was_starving {
+                        self.log.push(Event { tick, kind: EventKind::Starving { animal: resident.id } });
+                    }
+                    resident.health = resident.health.saturating_sub(5);
+                } else {
+                    resident.health = (resident.health + 1).min(MAX_LEVEL);
+                }
+                if self.rng.below(ILLNESS_ODDS) == 0 {
+                    resident.health = resident.health.saturating_sub(ILLNESS_DAMAGE);
+                    self.log.push(Event { tick, kind: EventKind::FellIll { animal: resident.id } });
+                }
+            }
+
+            enclosure.residents.retain(|resident| {
+                if resident.health == 0 {
+                    self.log.push(Event { tick, kind: EventKind::Died { animal: resident.id } });
+                }
+                resident.health > 0
+            });
+        }
+    }
+
+    pub fn run(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animals::{Eagle, Monkey, Penguin, Tiger};
+
+    fn daily() -> FeedingSchedule {
+        FeedingSchedule { first_tick: 1, every: 10 }
+    }
+
+    #[test]
+    fn enclosure_rules_come_from_the_traits() {
+        let mut zoo = Zoo::new(1);
+        let cats = zoo.add_enclosure(Enclosure::new("Big cats", 2, false, daily()));
+        let aviary = zoo.add_enclosure(Enclosure::new("Aviary", 5, true, daily()));
+
+        assert!(zoo.admit(cats, "Shere Khan", Box::new(Tiger { num_legs: 4 })).is_ok());
+        assert_eq!(
+            zoo.admit(cats, "Abu", Box::new(Monkey { num_legs: 2 })),
+            Err(AdmitError::Incompatible { with: "Tiger" })
+        );
+        assert!(zoo.admit(cats, "Rajah", Box::new(Tiger { num_legs: 4 })).is_ok());
+        assert_eq!(zoo.admit(cats, "Hobbes", Box::new(Tiger { num_legs: 4 })), Err(AdmitError::Full));
+
+        // the eagle needs a roof, a penguin doesn't fly but the eagle would eat it
+        let paddock = zoo.add_enclosure(Enclosure::new("Paddock", 5, false, daily()));
+        assert_eq!(zoo.admit(paddock, "Baldy", Box::new(Eagle::new())), Err(AdmitError::NeedsRoof));
+        assert!(zoo.admit(paddock, "Pingu", Box::new(Penguin { mass_kg: 30.0 })).is_ok());
+        assert!(zoo.admit(paddock, "Abu", Box::new(Monkey { num_legs: 2 })).is_ok());
+        assert!(zoo.admit(aviary, "Baldy", Box::new(Eagle::new())).is_ok());
+        let injured = Eagle { num_wings: 1, ..Eagle::new() };
+        assert_eq!(zoo.admit(paddock, "Lefty", Box::new(injured)), Err(AdmitError::Incompatible { with: "Penguin" }));
+        assert_eq!(zoo.admit(EnclosureId(9), "Nobody", Box::new(Eagle::new())), Err(AdmitError::UnknownEnclosure));
+
+        let admitted = zoo.log().iter().filter(|event| matches!(event.kind, EventKind::Admitted { .. })).count();
+        assert_eq!(admitted, 5);
+    }
+
+    #[test]
+    fn feeding_keeps_animals_alive() {
+        let mut zoo = Zoo::new(7);
+        let fed = zoo.add_enclosure(Enclosure::new("Fed", 4, false, daily()));
+        let forgotten = zoo.add_enclosure(Enclosure::new("Forgotten", 4, false, FeedingSchedule { first_tick: 1, every: 0 }));
+        let abu = zoo.admit(fed, "Abu", Box::new(Monkey { num_legs: 2 })).unwrap();
+        let khan = zoo.admit(forgotten, "Khan", Box::new(Tiger { num_legs: 4 })).unwrap();
+        zoo.run(200);
+
+        assert!(zoo.resident(abu).unwrap().hunger < STARVING);
+        assert!(zoo.resident(khan).is_none());
+        let khan_events: Vec<&EventKind> = zoo
+            .log()
+            .iter()
+            .map(|event| &event.kind)
+            .filter(|kind| matches!(kind, EventKind::Starving { animal } | EventKind::Died { animal } if *animal == khan))
+            .collect();
+        assert_eq!(khan_events, [&EventKind::Starving { animal: khan }, &EventKind::Died { animal: khan }]);
+
+        let feedings = zoo.log().iter().filter(|event| event.kind == EventKind::Fed { enclosure: fed, animals: 1 }).count();
+        assert_eq!(feedings, 20);
+        assert_eq!(zoo.log().iter().filter(|event| matches!(event.kind, EventKind::Fed { enclosure, .. } if enclosure == forgotten)).count(), 1);
+    }
+
+    #[test]
+    fn same_seed_same_log() {
+        let run = |seed| {
+            let mut zoo = Zoo::new(seed);
+            let troop = zoo.add_enclosure(Enclosure::new("Troop", 10, false, daily()));
+            for name in ["Abu", "Bubbles", "Cheeta", "Dunston"] {
+                zoo.admit(troop, name, Box::new(Monkey { num_legs: 2 })).unwrap();
+            }
+            zoo.run(1000);
+            zoo.log().iter().map(|event| event.to_string()).collect::<Vec<String>>()
+        };
+        let log = run(3);
+        assert_eq!(log, run(3));
+        assert_ne!(log, run(4));
+        assert!(log.iter().any(|line| line.ends_with("fell ill")));
+        assert_eq!(log[0], "tick 0: animal 1 admitted to enclosure 0");
+        assert_eq!(log[4], "tick 1: fed 4 animal(s) in enclosure 0");
+    }
+
+    #[test]
+    fn feeding_schedule() {
+        let schedule = FeedingSchedule { first_tick: 3, every: 4 };
+        let ticks: Vec<u32> = (0..16).filter(|tick| schedule.is_feeding_time(*tick)).collect();
+        assert_eq!(ticks, [3, 7, 11, 15]);
+    }
+}
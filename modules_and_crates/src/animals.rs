@@ -0,0 +1,99 @@
+// animals.rs is a module, it used to be an in-line module with one fn per sound
+// (say_meow, say_woof), now every animal is registered with a name and a sound
+// so adding a new animal is a call to register() instead of a new function
+
+// anything that can make a sound, "dyn Sound" lets the registry store different types together
+pub trait Sound{
+    fn sound(&self) -> String;
+}
+
+// the simplest sound, a fixed piece of text
+pub struct Says(pub &'static str);
+
+impl Sound for Says{
+    fn sound(&self) -> String{
+        self.0.to_string()
+    }
+}
+
+// every closure that returns a String is a Sound too, eg: Box::new(|| "Moo".repeat(2))
+impl<F: Fn() -> String> Sound for F{
+    fn sound(&self) -> String{
+        self()
+    }
+}
+
+// names are stored in lowercase, so "Cat" and "cat" are the same animal
+// a Vec keeps the animals in the order they were registered
+#[derive(Default)]
+pub struct AnimalRegistry{
+    animals: Vec<(String, Box<dyn Sound>)>,
+}
+
+impl AnimalRegistry{
+    pub fn new() -> AnimalRegistry{
+        AnimalRegistry{animals: Vec::new()}
+    }
+
+    // the animals that used to be say_meow() and say_woof()
+    pub fn with_defaults() -> AnimalRegistry{
+        let mut registry = AnimalRegistry::new();
+        registry.register("cat", Box::new(Says("Meow")));
+        registry.register("dog", Box::new(Says("Woof")));
+        registry
+    }
+
+    // registering a name again replaces its sound, the old one is returned
+    pub fn register(&mut self, name: &str, sound: Box<dyn Sound>) -> Option<Box<dyn Sound>>{
+        let name = name.to_lowercase();
+        match self.animals.iter_mut().find(|(known, _)| *known == name){
+            Some((_, old)) => Some(std::mem::replace(old, sound)),
+            None => {
+                self.animals.push((name, sound));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Sound>{
+        let name = name.to_lowercase();
+        self.animals.iter().find(|(known, _)| *known == name).map(|(_, sound)| sound.as_ref())
+    }
+
+    // the sound of the named animal, None if it was never registered
+    pub fn speak(&self, name: &str) -> Option<String>{
+        self.get(name).map(|animal| animal.sound())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str>{
+        self.animals.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn defaults_replace_the_old_functions(){
+        let registry = AnimalRegistry::with_defaults();
+        assert_eq!(registry.speak("cat"), Some("Meow".to_string()));
+        assert_eq!(registry.speak("Dog"), Some("Woof".to_string()));
+        assert_eq!(registry.speak("fox"), None);
+    }
+
+    #[test]
+    fn registering_new_animals(){
+        let mut registry = AnimalRegistry::with_defaults();
+        let times = 2;
+        assert!(registry.register("Cow", Box::new(move || "Moo".repeat(times))).is_none());
+        assert_eq!(registry.speak("cow"), Some("MooMoo".to_string()));
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["cat", "dog", "cow"]);
+
+        // same name again replaces the sound
+        let old = registry.register("cat", Box::new(Says("Purr")));
+        assert_eq!(old.map(|sound| sound.sound()), Some("Meow".to_string()));
+        assert_eq!(registry.speak("cat"), Some("Purr".to_string()));
+        assert_eq!(registry.names().count(), 3);
+    }
+}
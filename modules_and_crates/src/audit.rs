@@ -0,0 +1,177 @@
+// audit.rs is a module that checks the visibility of every item in a crate
+// tokenizer.rs -> splits Rust source into tokens, skipping comments and strings
+// modtree.rs   -> follows the "mod" declarations and collects the items with their visibility
+//
+// The declared visibility is not the whole story, an item is only as visible as the
+// modules around it: a "pub fn" inside a private "mod" can't be named outside the crate.
+// effective() works out the part of the crate that can really name each item.
+use std::fmt;
+use std::path::Path;
+
+pub mod modtree;
+pub mod tokenizer;
+
+pub use modtree::{AuditError, Item, ItemKind, ModuleTree, Visibility};
+
+// where an item can be named from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope {
+    // code inside this module and its children, an empty path is the crate root (the whole crate)
+    Module(Vec<String>),
+    // any crate, the item is part of the public API
+    World,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scope::World => write!(f, "public API"),
+            Scope::Module(module) if module.is_empty() => write!(f, "whole crate"),
+            Scope::Module(module) => write!(f, "only in {}", modtree::module_name(module)),
+        }
+    }
+}
+
+impl Scope {
+    // the narrower of two scopes, a deeper module is narrower than the module around it
+    fn narrowest(self, other: Scope) -> Scope {
+        match (self, other) {
+            (Scope::World, other) | (other, Scope::World) => other,
+            (Scope::Module(a), Scope::Module(b)) => Scope::Module(if a.len() >= b.len() { a } else { b }),
+        }
+    }
+
+    // reachable from the crate root means code in lib.rs/main.rs can name the item
+    pub fn reaches_root(&self) -> bool {
+        match self {
+            Scope::World => true,
+            Scope::Module(module) => module.is_empty(),
+        }
+    }
+}
+
+// the scope given by a visibility alone, for an item declared in "module"
+fn declared_scope(visibility: &Visibility, module: &[String]) -> Scope {
+    match visibility {
+        Visibility::Public => Scope::World,
+        Visibility::Crate => Scope::Module(Vec::new()),
+        Visibility::Private => Scope::Module(module.to_vec()),
+        Visibility::Super => Scope::Module(module[..module.len().saturating_sub(1)].to_vec()),
+        Visibility::InPath(path) => Scope::Module(path.clone()),
+    }
+}
+
+// narrows the item's own scope by every module around it (and by its type, for methods)
+pub fn effective(tree: &ModuleTree, item: &Item) -> Scope {
+    let mut scope = declared_scope(&item.visibility, &item.module);
+    for depth in 1..=item.module.len() {
+        let (parent, name) = (&item.module[..depth - 1], &item.module[depth - 1]);
+        let declaration = tree
+            .items
+            .iter()
+            .find(|m| m.kind == ItemKind::Mod && m.module == parent && m.name == *name);
+        if let Some(declaration) = declaration {
+            scope = scope.narrowest(declared_scope(&declaration.visibility, parent));
+        }
+    }
+    if let Some(owner) = &item.owner {
+        let ty = tree.items.iter().find(|t| {
+            matches!(t.kind, ItemKind::Struct | ItemKind::Enum | ItemKind::Union | ItemKind::Type)
+                && t.module == item.module
+                && t.name == *owner
+        });
+        if let Some(ty) = ty {
+            scope = scope.narrowest(effective(tree, ty));
+        }
+    }
+    scope
+}
+
+// reads the crate starting at its root file (src/lib.rs or src/main.rs) from disk
+pub fn audit_crate(root: &Path) -> Result<ModuleTree, AuditError> {
+    modtree::build(root, &|path: &Path| std::fs::read_to_string(path).ok())
+}
+
+// the text printed by the visibility_audit binary
+pub fn report(tree: &ModuleTree) -> String {
+    let rows: Vec<(&Item, Scope)> = tree.items.iter().map(|item| (item, effective(tree, item))).collect();
+    let width = rows.iter().map(|(item, _)| item.path().len()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for (item, scope) in &rows {
+        out += &format!(
+            "{:<6} {:<width$}  {:<22} {}\n",
+            item.kind.keyword(),
+            item.path(),
+            item.visibility.to_string(),
+            scope,
+            width = width
+        );
+    }
+
+    let unreachable: Vec<&(&Item, Scope)> = rows.iter().filter(|(_, scope)| !scope.reaches_root()).collect();
+    out += &format!("\n{} item(s) unreachable from the crate root:\n", unreachable.len());
+    for (item, scope) in unreachable {
+        out += &format!("  {} ({}:{}, {})\n", item.path(), item.file.display(), item.line, scope);
+    }
+
+    for warning in &tree.warnings {
+        out += &format!("warning: {}\n", warning);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn scopes(source: &str) -> Vec<(String, Scope)> {
+        let files: HashMap<PathBuf, String> = [(PathBuf::from("main.rs"), source.to_string())].into();
+        let tree = modtree::build(Path::new("main.rs"), &|path: &Path| files.get(path).cloned()).unwrap();
+        tree.items.iter().map(|item| (item.path(), effective(&tree, item))).collect()
+    }
+
+    fn module(path: &[&str]) -> Scope {
+        Scope::Module(path.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn modules_narrow_their_items() {
+        let source = "
+            mod module_in_main { fn this_module_fn_is_in_main() {} pub fn open() {} }
+            pub mod parent {
+                fn parent_fn() {}
+                pub mod child { pub fn child_fn() {} pub(super) fn up() {} }
+            }
+            pub(crate) struct Hidden;
+            impl Hidden { pub fn new() -> Hidden { Hidden } }
+        ";
+        assert_eq!(
+            scopes(source),
+            vec![
+                ("crate::module_in_main".to_string(), module(&[])),
+                ("crate::module_in_main::this_module_fn_is_in_main".to_string(), module(&["module_in_main"])),
+                ("crate::module_in_main::open".to_string(), module(&[])),
+                ("crate::parent".to_string(), Scope::World),
+                ("crate::parent::parent_fn".to_string(), module(&["parent"])),
+                ("crate::parent::child".to_string(), Scope::World),
+                ("crate::parent::child::child_fn".to_string(), Scope::World),
+                ("crate::parent::child::up".to_string(), module(&["parent"])),
+                ("crate::Hidden".to_string(), module(&[])),
+                ("crate::Hidden::new".to_string(), module(&[])),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_lists_unreachable_items() {
+        let files: HashMap<PathBuf, String> =
+            [(PathBuf::from("main.rs"), "mod a { fn hidden() {} }\nmod b;".to_string())].into();
+        let tree = modtree::build(Path::new("main.rs"), &|path: &Path| files.get(path).cloned()).unwrap();
+        let text = report(&tree);
+        assert!(text.contains("1 item(s) unreachable from the crate root:\n  crate::a::hidden (main.rs:1, only in crate::a)"));
+        assert!(text.contains("warning: main.rs:2: no file for mod crate::b"));
+    }
+}
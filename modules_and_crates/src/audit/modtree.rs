@@ -0,0 +1,491 @@
+// rebuilds the "mod" hierarchy of a crate from its source files and collects every item
+// with the visibility it was declared with
+//
+// where the code of "mod name;" lives depends on the file declaring it:
+// src/lib.rs, src/main.rs, war/mod.rs -> children sit next to the file: name.rs or name/mod.rs
+// garden.rs                           -> children sit in a folder named after it: garden/name.rs
+// an in-line "mod name { }" adds "name/" to the folder of its parent
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::tokenizer::{tokenize, Token, TokenKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visibility {
+    Private,           // no pub, or pub(self)
+    Crate,             // pub(crate)
+    Super,             // pub(super)
+    InPath(Vec<String>), // pub(in crate::a::b), stored without the leading "crate"
+    Public,            // pub
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Visibility::Private => write!(f, "private"),
+            Visibility::Crate => write!(f, "pub(crate)"),
+            Visibility::Super => write!(f, "pub(super)"),
+            Visibility::InPath(path) => write!(f, "pub(in {})", module_name(path)),
+            Visibility::Public => write!(f, "pub"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Mod,
+    Fn,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Const,
+    Static,
+    Type,
+    // an fn inside an inherent "impl Type { }" block
+    Method,
+}
+
+impl ItemKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            ItemKind::Mod => "mod",
+            ItemKind::Fn | ItemKind::Method => "fn",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Union => "union",
+            ItemKind::Trait => "trait",
+            ItemKind::Const => "const",
+            ItemKind::Static => "static",
+            ItemKind::Type => "type",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    // the module the item is declared in, empty for the crate root
+    pub module: Vec<String>,
+    // for methods, the type of the impl block
+    pub owner: Option<String>,
+    pub visibility: Visibility,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl Item {
+    // crate::parent::parent_fn, or crate::garden::Garden::new for a method
+    pub fn path(&self) -> String {
+        let mut path = module_name(&self.module);
+        if let Some(owner) = &self.owner {
+            path += "::";
+            path += owner;
+        }
+        path + "::" + &self.name
+    }
+}
+
+// crate, crate::war, crate::war::battleship
+pub fn module_name(module: &[String]) -> String {
+    std::iter::once("crate").chain(module.iter().map(String::as_str)).collect::<Vec<_>>().join("::")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleTree {
+    pub items: Vec<Item>,
+    // problems that did not stop the analysis, eg: a "mod x;" without a file
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditError {
+    pub file: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.file.display(), self.message)
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+// "load" reads a file, the real tool passes fs::read_to_string and the tests pass a HashMap lookup
+pub fn build(root: &Path, load: &dyn Fn(&Path) -> Option<String>) -> Result<ModuleTree, AuditError> {
+    let mut tree = ModuleTree { items: Vec::new(), warnings: Vec::new() };
+    let source = load(root).ok_or_else(|| AuditError {
+        file: root.to_path_buf(),
+        message: "cannot read the crate root".to_string(),
+    })?;
+    let dir = root.parent().map(Path::to_path_buf).unwrap_or_default();
+    parse_file(root, &source, Vec::new(), dir, load, &mut tree)?;
+    Ok(tree)
+}
+
+fn parse_file(
+    file: &Path,
+    source: &str,
+    module: Vec<String>,
+    dir: PathBuf,
+    load: &dyn Fn(&Path) -> Option<String>,
+    tree: &mut ModuleTree,
+) -> Result<(), AuditError> {
+    let tokens = tokenize(source).map_err(|e| AuditError { file: file.to_path_buf(), message: e.to_string() })?;
+    let mut parser = Parser { tokens: &tokens, pos: 0, file, load, tree };
+    parser.items(&module, &dir, None, false)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    file: &'a Path,
+    load: &'a dyn Fn(&Path) -> Option<String>,
+    tree: &'a mut ModuleTree,
+}
+
+impl Parser<'_> {
+    fn peek(&self, ahead: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + ahead)
+    }
+
+    fn ident(&self, ahead: usize) -> Option<&str> {
+        match &self.peek(ahead)?.kind {
+            TokenKind::Ident(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn error(&self, message: &str) -> AuditError {
+        let line = self.peek(0).or(self.tokens.last()).map_or(0, |t| t.line);
+        AuditError { file: self.file.to_path_buf(), message: format!("line {}: {}", line, message) }
+    }
+
+    // skips a (), [] or {} group, the current token is the opening bracket
+    fn skip_group(&mut self) -> Result<(), AuditError> {
+        let mut depth = 0;
+        while let Some(token) = self.peek(0) {
+            match token.kind {
+                TokenKind::Punct('(' | '[' | '{') => depth += 1,
+                TokenKind::Punct(')' | ']' | '}') => depth -= 1,
+                _ => (),
+            }
+            self.pos += 1;
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+        Err(self.error("unclosed bracket"))
+    }
+
+    // moves past the rest of an item: up to a ";" or over a { } body, whichever comes first
+    // brackets and parens are skipped as groups, so the ";" in [u8; 3] does not end the item
+    fn skip_item(&mut self, until_semicolon: bool) -> Result<(), AuditError> {
+        while let Some(token) = self.peek(0) {
+            match token.kind {
+                TokenKind::Punct(';') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                TokenKind::Punct('{') if !until_semicolon => return self.skip_group(),
+                TokenKind::Punct('(' | '[' | '{') => self.skip_group()?,
+                _ => self.pos += 1,
+            }
+        }
+        Err(self.error("unexpected end of file"))
+    }
+
+    fn visibility(&mut self) -> Visibility {
+        if self.ident(0) != Some("pub") {
+            return Visibility::Private;
+        }
+        self.pos += 1;
+        if !self.peek(0).is_some_and(|t| t.is_punct('(')) {
+            return Visibility::Public;
+        }
+        let visibility = match (self.ident(1), self.peek(2).map(|t| &t.kind)) {
+            (Some("crate"), Some(TokenKind::Punct(')'))) => Visibility::Crate,
+            (Some("super"), Some(TokenKind::Punct(')'))) => Visibility::Super,
+            (Some("self"), Some(TokenKind::Punct(')'))) => Visibility::Private,
+            (Some("in"), _) => {
+                let mut path = Vec::new();
+                let mut ahead = 2;
+                while let Some(token) = self.peek(ahead).filter(|t| !t.is_punct(')')) {
+                    if let TokenKind::Ident(name) = &token.kind {
+                        path.push(name.clone());
+                    }
+                    ahead += 1;
+                }
+                if path.first().map(String::as_str) == Some("crate") {
+                    path.remove(0);
+                }
+                Visibility::InPath(path)
+            }
+            // "pub (" followed by something else, eg: a tuple struct field, is plain pub
+            _ => return Visibility::Public,
+        };
+        // skip "( ... )"
+        while self.peek(0).is_some_and(|t| !t.is_punct(')')) {
+            self.pos += 1;
+        }
+        self.pos += 1;
+        visibility
+    }
+
+    fn push(&mut self, kind: ItemKind, name: String, module: &[String], owner: Option<&str>, visibility: Visibility, line: usize) {
+        self.tree.items.push(Item {
+            kind,
+            name,
+            module: module.to_vec(),
+            owner: owner.map(str::to_string),
+            visibility,
+            file: self.file.to_path_buf(),
+            line,
+        });
+    }
+
+    // reads items until the end of the file, or until the "}" closing the current block
+    // "in_block" is true inside mod name { } and impl Type { }, where the "}" must be found
+    fn items(&mut self, module: &[String], dir: &Path, owner: Option<&str>, in_block: bool) -> Result<(), AuditError> {
+        while let Some(token) = self.peek(0) {
+            let line = token.line;
+            match &token.kind {
+                TokenKind::Punct('}') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                // attributes: #[...] and #![...]
+                TokenKind::Punct('#') => {
+                    self.pos += 1;
+                    if self.peek(0).is_some_and(|t| t.is_punct('!')) {
+                        self.pos += 1;
+                    }
+                    self.skip_group()?;
+                    continue;
+                }
+                TokenKind::Punct(';') => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => (),
+            }
+
+            let visibility = self.visibility();
+            // qualifiers in front of fn: const fn, async fn, unsafe fn, extern "C" fn
+            while matches!(self.ident(0), Some("async" | "unsafe" | "default"))
+                || (self.ident(0) == Some("const") && matches!(self.ident(1), Some("fn" | "unsafe" | "async" | "extern")))
+                || (self.ident(0) == Some("extern") && self.ident(1) != Some("crate") && !self.peek(1).is_some_and(|t| t.is_punct('{')))
+            {
+                self.pos += 1;
+                if self.peek(0).is_some_and(|t| t.kind == TokenKind::Literal) {
+                    self.pos += 1;
+                }
+            }
+
+            let keyword = self.ident(0).map(str::to_string);
+            let name = self.ident(1).map(str::to_string);
+            let kind = match keyword.as_deref() {
+                Some("mod") => {
+                    self.module(module, dir, visibility, line)?;
+                    continue;
+                }
+                Some("impl") => {
+                    self.impl_block(module, dir)?;
+                    continue;
+                }
+                Some("fn") if owner.is_some() => Some(ItemKind::Method),
+                Some("fn") => Some(ItemKind::Fn),
+                Some("struct") => Some(ItemKind::Struct),
+                Some("enum") => Some(ItemKind::Enum),
+                Some("union") if name.is_some() => Some(ItemKind::Union),
+                Some("trait") => Some(ItemKind::Trait),
+                Some("const") if name.as_deref() != Some("_") => Some(ItemKind::Const),
+                Some("static") => Some(ItemKind::Static),
+                Some("type") => Some(ItemKind::Type),
+                _ => None,
+            };
+
+            match (kind, name) {
+                (Some(kind), Some(name)) => {
+                    self.pos += 2;
+                    self.push(kind, name, module, owner, visibility, line);
+                    let until_semicolon = matches!(kind, ItemKind::Const | ItemKind::Static | ItemKind::Type);
+                    self.skip_item(until_semicolon)?;
+                }
+                // use, extern crate, macro_rules!, macro calls: not items we report
+                _ => {
+                    self.pos += 1;
+                    self.skip_item(false)?;
+                }
+            }
+        }
+        if in_block {
+            return Err(self.error("unclosed block"));
+        }
+        Ok(())
+    }
+
+    fn module(&mut self, module: &[String], dir: &Path, visibility: Visibility, line: usize) -> Result<(), AuditError> {
+        let name = self.ident(1).ok_or_else(|| self.error("expected a module name"))?.to_string();
+        self.pos += 2;
+        self.push(ItemKind::Mod, name.clone(), module, None, visibility, line);
+
+        let mut child = module.to_vec();
+        child.push(name.clone());
+        let child_dir = dir.join(&name);
+
+        match self.peek(0).map(|t| &t.kind) {
+            // in-line module, its items follow directly
+            Some(TokenKind::Punct('{')) => {
+                self.pos += 1;
+                self.items(&child, &child_dir, None, true)
+            }
+            // module in its own file: dir/name.rs or dir/name/mod.rs
+            Some(TokenKind::Punct(';')) => {
+                self.pos += 1;
+                let candidates = [dir.join(format!("{}.rs", name)), child_dir.join("mod.rs")];
+                let found = candidates.iter().find_map(|path| (self.load)(path).map(|source| (path, source)));
+                match found {
+                    // both layouts keep the children of "name" in dir/name/
+                    Some((path, source)) => parse_file(path, &source, child, child_dir, self.load, self.tree),
+                    None => {
+                        self.tree.warnings.push(format!(
+                            "{}:{}: no file for mod {}, looked for {} and {}",
+                            self.file.display(),
+                            line,
+                            module_name(&child),
+                            candidates[0].display(),
+                            candidates[1].display()
+                        ));
+                        Ok(())
+                    }
+                }
+            }
+            _ => Err(self.error("expected ; or { after the module name")),
+        }
+    }
+
+    // only inherent impls (impl Type { }) have methods with their own visibility,
+    // the methods of "impl Trait for Type" are as visible as the trait
+    fn impl_block(&mut self, module: &[String], dir: &Path) -> Result<(), AuditError> {
+        self.pos += 1;
+        let mut owner = None;
+        let mut is_trait_impl = false;
+        let mut angle_depth = 0;
+        while let Some(token) = self.peek(0) {
+            match &token.kind {
+                TokenKind::Punct('{') if angle_depth == 0 => break,
+                TokenKind::Punct('<') => angle_depth += 1,
+                TokenKind::Punct('>') if angle_depth > 0 => angle_depth -= 1,
+                TokenKind::Ident(word) if angle_depth == 0 && word == "for" => is_trait_impl = true,
+                TokenKind::Ident(word) if angle_depth == 0 && word == "where" => {
+                    while self.peek(0).is_some_and(|t| !t.is_punct('{')) {
+                        self.pos += 1;
+                    }
+                    break;
+                }
+                // the last plain identifier before the body is the type, eg: impl<T> Point<T>
+                TokenKind::Ident(word) if angle_depth == 0 && word != "dyn" && word != "unsafe" => owner = Some(word.clone()),
+                _ => (),
+            }
+            self.pos += 1;
+        }
+        if is_trait_impl {
+            return self.skip_group();
+        }
+        self.pos += 1;
+        self.items(module, dir, Some(owner.as_deref().unwrap_or("_")), true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    pub(crate) fn build_from(files: &[(&str, &str)], root: &str) -> ModuleTree {
+        let files: HashMap<PathBuf, String> = files.iter().map(|(path, source)| (PathBuf::from(path), source.to_string())).collect();
+        build(Path::new(root), &|path: &Path| files.get(path).cloned()).unwrap()
+    }
+
+    fn paths(tree: &ModuleTree) -> Vec<String> {
+        tree.items.iter().map(Item::path).collect()
+    }
+
+    #[test]
+    fn follows_both_file_layouts() {
+        let tree = build_from(
+            &[
+                ("src/lib.rs", "pub mod garden; mod war; mod missing;"),
+                ("src/garden.rs", "pub mod vegetable; pub fn garden_name() {}"),
+                ("src/garden/vegetable.rs", "pub struct Banana { pub ripen: bool }"),
+                ("src/war/mod.rs", "pub mod battleship; pub fn launc_attack() {}"),
+                ("src/war/battleship/mod.rs", "// empty"),
+            ],
+            "src/lib.rs",
+        );
+        assert_eq!(
+            paths(&tree),
+            vec![
+                "crate::garden",
+                "crate::garden::vegetable",
+                "crate::garden::vegetable::Banana",
+                "crate::garden::garden_name",
+                "crate::war",
+                "crate::war::battleship",
+                "crate::war::launc_attack",
+                "crate::missing",
+            ]
+        );
+        assert_eq!(tree.warnings.len(), 1);
+        assert!(tree.warnings[0].contains("src/missing.rs"));
+        assert_eq!(tree.items[3].file, PathBuf::from("src/garden.rs"));
+    }
+
+    #[test]
+    fn reads_visibility_and_skips_bodies() {
+        let source = r#"
+            #![allow(dead_code)]
+            mod parent {
+                fn parent_fn() { let s = "fn fake() {}"; }
+                pub(super) const LIMIT: [u8; 2] = [1, 2];
+                pub(in crate::parent) static NAME: &str = "x";
+                pub(crate) struct Point<T>(pub T, T);
+                impl<T: Clone> Point<T> where T: Copy {
+                    pub fn x(&self) -> &T { &self.0 }
+                    const fn zero() -> u8 { 0 }
+                }
+                impl Clone for Point<u8> { fn clone(&self) -> Self { todo!() } }
+                pub mod child { pub unsafe extern "C" fn child_fn() {} }
+            }
+            use parent::child;
+            #[derive(Debug)]
+            pub enum Leafy { Spinach, Cabbage }
+            macro_rules! nothing { () => {} }
+            pub trait Sound { fn sound(&self) -> String; }
+            type Alias = u8;
+        "#;
+        let tree = build_from(&[("main.rs", source)], "main.rs");
+        let summary: Vec<String> = tree.items.iter().map(|item| format!("{} {} {}", item.kind.keyword(), item.path(), item.visibility)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "mod crate::parent private",
+                "fn crate::parent::parent_fn private",
+                "const crate::parent::LIMIT pub(super)",
+                "static crate::parent::NAME pub(in crate::parent)",
+                "struct crate::parent::Point pub(crate)",
+                "fn crate::parent::Point::x pub",
+                "fn crate::parent::Point::zero private",
+                "mod crate::parent::child pub",
+                "fn crate::parent::child::child_fn pub",
+                "enum crate::Leafy pub",
+                "trait crate::Sound pub",
+                "type crate::Alias private",
+            ]
+        );
+        assert_eq!(tree.items[1].line, 4);
+    }
+}
@@ -0,0 +1,310 @@
+// a hand written tokenizer for Rust source, good enough to find modules and items
+// it does not need to understand expressions, only to never mistake the inside of a
+// string, char or comment for code, eg: "mod x;" inside a string is not a module
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    // identifiers and keywords, r#type is returned as Ident("type")
+    Ident(String),
+    // a single punctuation character, "::" comes out as two Punct(':')
+    Punct(char),
+    // strings, chars and numbers, their value is never needed
+    Literal,
+    // 'a, 'static
+    Lifetime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub line: usize, // starts at 1
+}
+
+impl Token {
+    pub fn is_ident(&self, word: &str) -> bool {
+        matches!(&self.kind, TokenKind::Ident(name) if name == word)
+    }
+
+    pub fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct(c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenizeError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for TokenizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TokenizeError {}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Lexer {
+    fn peek(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.pos + ahead).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: &'static str) -> TokenizeError {
+        TokenizeError { line: self.line, message }
+    }
+
+    fn line_comment(&mut self) {
+        while let Some(c) = self.peek(0) {
+            if c == '\n' {
+                break;
+            }
+            self.bump();
+        }
+    }
+
+    // block comments nest in Rust: /* a /* b */ still a comment */
+    fn block_comment(&mut self) -> Result<(), TokenizeError> {
+        let start = self.error("unterminated block comment");
+        self.pos += 2;
+        let mut depth = 1;
+        while depth > 0 {
+            match (self.bump(), self.peek(0)) {
+                (Some('/'), Some('*')) => {
+                    self.bump();
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
+                    self.bump();
+                    depth -= 1;
+                }
+                (Some(_), _) => (),
+                (None, _) => return Err(start),
+            }
+        }
+        Ok(())
+    }
+
+    // a "..." string, the opening quote is the next char
+    fn string(&mut self) -> Result<(), TokenizeError> {
+        let start = self.error("unterminated string");
+        self.bump();
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    self.bump();
+                }
+                Some('"') => return Ok(()),
+                Some(_) => (),
+                None => return Err(start),
+            }
+        }
+    }
+
+    // r"...", r#"..."#, r##"..."## and so on, the "r" is already consumed
+    fn raw_string(&mut self) -> Result<(), TokenizeError> {
+        let start = self.error("unterminated raw string");
+        let mut hashes = 0;
+        while self.peek(0) == Some('#') {
+            self.bump();
+            hashes += 1;
+        }
+        if self.bump() != Some('"') {
+            return Err(self.error("invalid raw string"));
+        }
+        loop {
+            match self.bump() {
+                Some('"') if (0..hashes).all(|i| self.peek(i) == Some('#')) => {
+                    self.pos += hashes;
+                    return Ok(());
+                }
+                Some(_) => (),
+                None => return Err(start),
+            }
+        }
+    }
+
+    // after a quote we either have a char literal 'x', '\n' or a lifetime 'a
+    fn quote(&mut self) -> Result<TokenKind, TokenizeError> {
+        self.bump();
+        match (self.peek(0), self.peek(1)) {
+            // an escape like '\n', '\'' or '\u{1F600}', skip the backslash and the escaped char
+            // then read up to the closing quote
+            (Some('\\'), _) => {
+                self.pos += 2;
+                loop {
+                    match self.bump() {
+                        Some('\'') => return Ok(TokenKind::Literal),
+                        Some('\n') | None => return Err(self.error("unterminated char literal")),
+                        Some(_) => (),
+                    }
+                }
+            }
+            (Some(_), Some('\'')) => {
+                self.pos += 2;
+                Ok(TokenKind::Literal)
+            }
+            (Some(c), _) if is_ident_start(c) => {
+                while self.peek(0).is_some_and(is_ident_continue) {
+                    self.bump();
+                }
+                Ok(TokenKind::Lifetime)
+            }
+            _ => Err(self.error("invalid char literal")),
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek(0).filter(|c| is_ident_continue(*c)) {
+            word.push(c);
+            self.bump();
+        }
+        word
+    }
+
+    fn number(&mut self) {
+        while let Some(c) = self.peek(0) {
+            // a "." only belongs to the number when a digit follows, so 0..10 stays three tokens
+            let fraction = c == '.' && self.peek(1).is_some_and(|d| d.is_ascii_digit());
+            if !(is_ident_continue(c) || fraction) {
+                break;
+            }
+            self.bump();
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Token>, TokenizeError> {
+    let mut lexer = Lexer { chars: source.chars().collect(), pos: 0, line: 1 };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = lexer.peek(0) {
+        let line = lexer.line;
+        let kind = match (c, lexer.peek(1)) {
+            (c, _) if c.is_whitespace() => {
+                lexer.bump();
+                continue;
+            }
+            ('/', Some('/')) => {
+                lexer.line_comment();
+                continue;
+            }
+            ('/', Some('*')) => {
+                lexer.block_comment()?;
+                continue;
+            }
+            ('"', _) => {
+                lexer.string()?;
+                TokenKind::Literal
+            }
+            ('\'', _) => lexer.quote()?,
+            (c, _) if c.is_ascii_digit() => {
+                lexer.number();
+                TokenKind::Literal
+            }
+            (c, _) if is_ident_start(c) => {
+                let word = lexer.word();
+                match (word.as_str(), lexer.peek(0), lexer.peek(1)) {
+                    // raw identifier, r#type is the identifier "type"
+                    ("r", Some('#'), Some(next)) if is_ident_start(next) => {
+                        lexer.bump();
+                        TokenKind::Ident(lexer.word())
+                    }
+                    ("r" | "br" | "cr", Some('"' | '#'), _) => {
+                        lexer.raw_string()?;
+                        TokenKind::Literal
+                    }
+                    ("b" | "c", Some('"'), _) => {
+                        lexer.string()?;
+                        TokenKind::Literal
+                    }
+                    ("b", Some('\''), _) => {
+                        lexer.quote()?;
+                        TokenKind::Literal
+                    }
+                    _ => TokenKind::Ident(word),
+                }
+            }
+            (c, _) => {
+                lexer.bump();
+                TokenKind::Punct(c)
+            }
+        };
+        tokens.push(Token { kind, line });
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        tokenize(source).unwrap().into_iter().map(|token| token.kind).collect()
+    }
+
+    fn ident(name: &str) -> TokenKind {
+        TokenKind::Ident(name.to_string())
+    }
+
+    #[test]
+    fn skips_comments_and_strings() {
+        let source = r###"
+            // mod hidden;
+            /* mod also /* nested */ hidden; */
+            let s = "mod x; \" still string";
+            let r = r#"mod y; "quoted" "#;
+            mod real;
+        "###;
+        let idents: Vec<TokenKind> = kinds(source).into_iter().filter(|k| matches!(k, TokenKind::Ident(_))).collect();
+        assert_eq!(idents, vec![ident("let"), ident("s"), ident("let"), ident("r"), ident("mod"), ident("real")]);
+    }
+
+    #[test]
+    fn chars_lifetimes_and_raw_idents() {
+        assert_eq!(
+            kinds("fn f<'a>(x: &'a u8) -> char { '{' } r#mod b'x' '\\''"),
+            vec![
+                ident("fn"), ident("f"), TokenKind::Punct('<'), TokenKind::Lifetime, TokenKind::Punct('>'),
+                TokenKind::Punct('('), ident("x"), TokenKind::Punct(':'), TokenKind::Punct('&'), TokenKind::Lifetime,
+                ident("u8"), TokenKind::Punct(')'), TokenKind::Punct('-'), TokenKind::Punct('>'), ident("char"),
+                TokenKind::Punct('{'), TokenKind::Literal, TokenKind::Punct('}'), ident("mod"), TokenKind::Literal,
+                TokenKind::Literal,
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_lines_and_reports_errors() {
+        let tokens = tokenize("a\n/* \n */ b\n\"x\ny\" c").unwrap();
+        let lines: Vec<usize> = tokens.iter().map(|t| t.line).collect();
+        assert_eq!(lines, vec![1, 3, 4, 5]);
+        assert_eq!(tokenize("x /* open").unwrap_err().line, 1);
+        assert_eq!(tokenize("\n\"open").unwrap_err().message, "unterminated string");
+        assert_eq!(kinds("0..10"), vec![TokenKind::Literal, TokenKind::Punct('.'), TokenKind::Punct('.'), TokenKind::Literal]);
+    }
+}
@@ -0,0 +1,261 @@
+// A terminal front-end for the war::battleship engine, you play against the AI
+// each file in src/bin is a separate binary crate, this one is run with
+// cargo run --bin battleship -- [--seed N] [--load FILE] [--script FILE]
+//
+// --seed N       -> seed for the AI and the random fleets, the same seed gives the same game
+// --load FILE    -> continue a game saved with the "save" command
+// --script FILE  -> read the commands from FILE instead of stdin, no prompts are shown
+//                   this replays a move script, used by the tests in tests/battleship_cli.rs
+//
+// commands: a cell like B7 to fire, "save FILE", "load FILE", "help", "quit"
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use modules_and_crates::war::battleship::save::{self, SavedGame};
+use modules_and_crates::war::battleship::{Ai, Board, Coord, Game, Player, Rng, ShotResult, BOARD_SIZE};
+
+const HELP: &str = "Fire with a cell like B7 (row A-J, column 1-10)
+Other commands: save FILE, load FILE, help, quit";
+
+struct Options {
+    seed: u64,
+    load: Option<String>,
+    script: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options { seed: 42, load: None, script: None };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--seed" => {
+                let seed = value()?;
+                options.seed = seed.parse().map_err(|_| format!("bad seed {:?}", seed))?;
+            }
+            "--load" => options.load = Some(value()?),
+            "--script" => options.script = Some(value()?),
+            _ => return Err(format!("unknown argument {:?}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+// the human is Player::One, the AI is Player::Two
+struct Session {
+    game: Game,
+    seed: u64,
+    ai: Ai,
+}
+
+impl Session {
+    fn new(seed: u64) -> Session {
+        let mut rng = Rng::new(seed);
+        let human = Board::random_fleet(&mut rng);
+        let computer = Board::random_fleet(&mut rng);
+        let game = Game::new(human, computer).expect("random fleets are complete");
+        Session { game, seed, ai: Ai::new(seed) }
+    }
+
+    // the AI is rebuilt by replaying its turns on a copy of the board, choosing again moves its
+    // random numbers to where they were when the game was saved, so the game continues the same way
+    fn from_saved(saved: SavedGame) -> Session {
+        let mut ai = Ai::new(saved.seed);
+        let human = saved.game.board(Player::One);
+        let mut replay = Board::new();
+        for ship in human.ships() {
+            replay.place(ship.kind, ship.start, ship.orientation).expect("ships were valid when loaded");
+        }
+        for at in human.shots() {
+            ai.choose(&replay);
+            let result = replay.fire(*at).expect("shots were valid when loaded");
+            ai.record(*at, result);
+        }
+        Session { game: saved.game, seed: saved.seed, ai }
+    }
+}
+
+// ------ Rendering ------
+// . -> water, S -> ship, X -> hit, o -> miss
+// the enemy board never shows the ships that have not been hit
+fn cell_symbol(board: &Board, at: &Coord, show_ships: bool) -> char {
+    match (board.ship_at(at).is_some(), board.is_fired(at)) {
+        (true, true) => 'X',
+        (false, true) => 'o',
+        (true, false) if show_ships => 'S',
+        _ => '.',
+    }
+}
+
+fn render(game: &Game) -> String {
+    let header: String = (1..=BOARD_SIZE).map(|col| format!("{:>3}", col)).collect();
+    let mut out = format!("   {:<width$}     {}\n", "Your fleet", "Enemy waters", width = BOARD_SIZE as usize * 3);
+    out += &format!("  {}     {}\n", header, header);
+    for row in 0..BOARD_SIZE {
+        let line = |board: &Board, show_ships: bool| -> String {
+            (0..BOARD_SIZE)
+                .map(|col| format!("{:>3}", cell_symbol(board, &Coord::new(row, col), show_ships)))
+                .collect()
+        };
+        let letter = (b'A' + row) as char;
+        out += &format!(
+            "{} {}   {} {}\n",
+            letter,
+            line(game.board(Player::One), true),
+            letter,
+            line(game.board(Player::Two), false)
+        );
+    }
+    out
+}
+
+fn describe(result: ShotResult) -> String {
+    match result {
+        ShotResult::Miss => "miss".to_string(),
+        ShotResult::Hit => "hit".to_string(),
+        ShotResult::Sunk(kind) => format!("hit and sunk the {}", kind.name()),
+    }
+}
+
+// ------ Commands ------
+// returns false when the session should end
+fn run_command(session: &mut Session, line: &str) -> bool {
+    // the path is the rest of the line, so it can contain spaces
+    let line = line.trim();
+    let (command, rest) = match line.split_once(char::is_whitespace) {
+        Some((command, rest)) => (command, rest.trim()),
+        None => (line, ""),
+    };
+    match (command, rest) {
+        ("", _) => true,
+        ("quit", _) => false,
+        ("help", _) => {
+            println!("{}", HELP);
+            true
+        }
+        ("save", path) if !path.is_empty() => {
+            match fs::write(path, save::save(&session.game, session.seed)) {
+                Ok(()) => println!("Saved to {}", path),
+                Err(error) => println!("Could not save: {}", error),
+            }
+            true
+        }
+        ("load", path) if !path.is_empty() => {
+            match load_file(path) {
+                Ok(loaded) => {
+                    *session = loaded;
+                    println!("Loaded {}", path);
+                    print!("{}", render(&session.game));
+                }
+                Err(error) => println!("Could not load: {}", error),
+            }
+            true
+        }
+        ("save" | "load", _) => {
+            println!("{} needs a file path, eg: {} game.txt", command, command);
+            true
+        }
+        // one word is a cell, eg: "B7", and so is a whole line that parses as one, eg: "B 7"
+        (cell, "") => fire(session, cell),
+        _ if line.parse::<Coord>().is_ok() => fire(session, line),
+        _ => {
+            println!("Unknown command {:?}, type help", line);
+            true
+        }
+    }
+}
+
+fn fire(session: &mut Session, cell: &str) -> bool {
+    let at: Coord = match cell.parse() {
+        Ok(at) => at,
+        Err(error) => {
+            println!("Invalid cell {:?}: {}", cell, error);
+            return true;
+        }
+    };
+    match session.game.fire(Player::One, at) {
+        Ok(result) => println!("You fire at {}: {}", at, describe(result)),
+        Err(error) => {
+            println!("Cannot fire at {}: {}", at, error);
+            return true;
+        }
+    }
+
+    if session.game.winner().is_none() {
+        let target = session.ai.choose(session.game.board(Player::One));
+        let result = session.game.fire(Player::Two, target).expect("the AI only picks open cells");
+        session.ai.record(target, result);
+        println!("Enemy fires at {}: {}", target, describe(result));
+    }
+    print!("{}", render(&session.game));
+
+    match session.game.winner() {
+        Some(Player::One) => println!("You win!"),
+        Some(Player::Two) => println!("You lose, the enemy sank your fleet"),
+        None => return true,
+    }
+    false
+}
+
+fn load_file(path: &str) -> Result<Session, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let saved = save::load(&text).map_err(|e| e.to_string())?;
+    Ok(Session::from_saved(saved))
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(2);
+    });
+
+    let mut session = match &options.load {
+        Some(path) => load_file(path).unwrap_or_else(|error| {
+            eprintln!("Could not load {}: {}", path, error);
+            process::exit(1);
+        }),
+        None => Session::new(options.seed),
+    };
+
+    // stdin and a script file are both read line by line through the BufRead trait
+    let (input, interactive): (Box<dyn BufRead>, bool) = match &options.script {
+        Some(path) => match fs::File::open(path) {
+            Ok(file) => (Box::new(io::BufReader::new(file)), false),
+            Err(error) => {
+                eprintln!("Could not open {}: {}", path, error);
+                process::exit(1);
+            }
+        },
+        None => (Box::new(io::stdin().lock()), true),
+    };
+
+    if interactive {
+        println!("{}", HELP);
+    }
+    print!("{}", render(&session.game));
+
+    let mut lines = input.lines();
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush().ok();
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        // lines starting with # are comments in scripts
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if !interactive {
+            println!("> {}", line);
+        }
+        if !run_command(&mut session, line) {
+            break;
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Prints the declared and effective visibility of every item in a crate, and the items
+// that code in the crate root can't reach
+// cargo run --bin visibility_audit -- src/main.rs
+// with no arguments it audits src/lib.rs, more than one root file can be given
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use modules_and_crates::audit;
+
+fn main() {
+    let mut roots: Vec<PathBuf> = env::args().skip(1).map(PathBuf::from).collect();
+    if roots.is_empty() {
+        roots.push(PathBuf::from("src/lib.rs"));
+    }
+
+    let mut failed = false;
+    for root in &roots {
+        println!("== {} ==", root.display());
+        match audit::audit_crate(root) {
+            Ok(tree) => print!("{}", audit::report(&tree)),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
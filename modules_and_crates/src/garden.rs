@@ -6,6 +6,69 @@ pub mod vegetable;
 // to use the code within vegetable module, the compiler looks
 // for a file garden/vegetable.rs
 
+// the garden management model, each sub-module lives in the garden/ folder
+pub mod date;    // garden/date.rs -> calendar dates for planting
+pub mod harvest; // garden/harvest.rs -> what can be picked and when
+pub mod plot;    // garden/plot.rs -> grid of cells holding plantings
+
+use std::fmt;
+
+use plot::Plot;
+
 pub fn garden_name(){
     println!("This is Victoria Gardens\n");
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GardenError{
+    // the garden already has a plot with this name
+    DuplicatePlot(String),
+}
+
+impl fmt::Display for GardenError{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            GardenError::DuplicatePlot(name) => write!(f, "the garden already has a plot named {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for GardenError {}
+
+// a garden is a named collection of plots
+#[derive(Debug, Clone)]
+pub struct Garden{
+    name: String,
+    plots: Vec<Plot>,
+}
+
+impl Garden{
+    pub fn new(name: &str) -> Garden{
+        Garden{name: name.to_string(), plots: Vec::new()}
+    }
+
+    pub fn name(&self) -> &str{
+        &self.name
+    }
+
+    // plots are found by name, so two plots can't share one
+    pub fn add_plot(&mut self, plot: Plot) -> Result<(), GardenError>{
+        if self.plot(plot.name()).is_some(){
+            return Err(GardenError::DuplicatePlot(plot.name().to_string()));
+        }
+        self.plots.push(plot);
+        Ok(())
+    }
+
+    pub fn plots(&self) -> &[Plot]{
+        &self.plots
+    }
+
+    pub fn plot(&self, name: &str) -> Option<&Plot>{
+        self.plots.iter().find(|plot| plot.name() == name)
+    }
+
+    pub fn plot_mut(&mut self, name: &str) -> Option<&mut Plot>{
+        self.plots.iter_mut().find(|plot| plot.name() == name)
+    }
+}
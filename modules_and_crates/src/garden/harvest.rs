@@ -0,0 +1,152 @@
+// a sub module of garden, works out what can be picked and when
+use super::date::Date;
+use super::plot::{Crop, Planting};
+use super::vegetable::{Banana, Leafy, Ripeness};
+use super::Garden;
+
+// where a crop is and the days it can be picked, from "opens" up to (not including) "closes"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HarvestWindow {
+    pub plot: String,
+    pub row: usize,
+    pub col: usize,
+    pub crop: Crop,
+    pub opens: Date,
+    pub closes: Date,
+}
+
+impl HarvestWindow {
+    fn new(plot: &str, (row, col): (usize, usize), planting: &Planting) -> HarvestWindow {
+        HarvestWindow {
+            plot: plot.to_string(),
+            row,
+            col,
+            crop: planting.crop,
+            opens: planting.ripe_on(),
+            closes: planting.overripe_on(),
+        }
+    }
+
+    pub fn contains(&self, date: &Date) -> bool {
+        self.opens <= *date && *date < self.closes
+    }
+}
+
+// what comes out of the ground when a crop is picked
+#[derive(Debug, PartialEq, Eq)]
+pub enum Harvested {
+    Leafy(Leafy),
+    Banana(Banana),
+}
+
+// every planting in the garden with its harvest window, the earliest window first
+pub fn schedule(garden: &Garden) -> Vec<HarvestWindow> {
+    let mut windows: Vec<HarvestWindow> = garden
+        .plots()
+        .iter()
+        .flat_map(|plot| plot.plantings().map(move |(cell, planting)| HarvestWindow::new(plot.name(), cell, planting)))
+        .collect();
+    windows.sort_by_key(|window| window.opens);
+    windows
+}
+
+// the crops that are ripe on the given date
+pub fn pickable_on(garden: &Garden, date: &Date) -> Vec<HarvestWindow> {
+    schedule(garden).into_iter().filter(|window| window.contains(date)).collect()
+}
+
+// the first date on or after "from" when something can be picked
+pub fn next_harvest(garden: &Garden, from: &Date) -> Option<Date> {
+    schedule(garden)
+        .iter()
+        .filter(|window| window.closes > *from)
+        .map(|window| window.opens.max(*from))
+        .min()
+}
+
+// picks every ripe crop, the cells become empty again
+// overripe crops are left in the ground, see spoiled_on()
+pub fn harvest(garden: &mut Garden, date: &Date) -> Vec<Harvested> {
+    let mut picked = Vec::new();
+    for window in pickable_on(garden, date) {
+        let Some(plot) = garden.plot_mut(&window.plot) else { continue };
+        if let Some(planting) = plot.remove(window.row, window.col) {
+            picked.push(match planting.crop {
+                Crop::Leafy(leafy) => Harvested::Leafy(leafy),
+                Crop::Banana => Harvested::Banana(Banana::from(Ripeness::Ripe)),
+            });
+        }
+    }
+    picked
+}
+
+// the crops that were not picked in time
+pub fn spoiled_on(garden: &Garden, date: &Date) -> Vec<HarvestWindow> {
+    schedule(garden).into_iter().filter(|window| window.closes <= *date).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::garden::plot::Plot;
+    use crate::garden::GardenError;
+
+    fn day(n: i64) -> Date {
+        Date::new(2024, 3, 1).unwrap().add_days(n)
+    }
+
+    fn sample_garden() -> Garden {
+        let mut north = Plot::new("north", 2, 2);
+        north.plant(0, 0, Crop::Leafy(Leafy::Spinach), day(0)).unwrap(); // ripe 40..55
+        north.plant(1, 1, Crop::Leafy(Leafy::Coriander), day(5)).unwrap(); // ripe 35..45
+        let mut south = Plot::new("south", 1, 1);
+        south.plant(0, 0, Crop::Banana, day(0)).unwrap(); // ripe 270..284
+
+        let mut garden = Garden::new("Victoria Gardens");
+        garden.add_plot(north).unwrap();
+        garden.add_plot(south).unwrap();
+        garden
+    }
+
+    #[test]
+    fn schedule_is_sorted_by_opening_date() {
+        let crops: Vec<Crop> = schedule(&sample_garden()).iter().map(|w| w.crop).collect();
+        assert_eq!(crops, vec![Crop::Leafy(Leafy::Coriander), Crop::Leafy(Leafy::Spinach), Crop::Banana]);
+    }
+
+    #[test]
+    fn lists_what_can_be_picked_on_a_date() {
+        let garden = sample_garden();
+        assert!(pickable_on(&garden, &day(34)).is_empty());
+        assert_eq!(pickable_on(&garden, &day(35)).len(), 1);
+        assert_eq!(pickable_on(&garden, &day(42)).len(), 2);
+        assert_eq!(spoiled_on(&garden, &day(50)).len(), 1);
+        assert_eq!(next_harvest(&garden, &day(0)), Some(day(35)));
+        assert_eq!(next_harvest(&garden, &day(60)), Some(day(270)));
+        assert_eq!(next_harvest(&garden, &day(300)), None);
+    }
+
+    #[test]
+    fn harvesting_empties_the_cells() {
+        let mut garden = sample_garden();
+        let picked = harvest(&mut garden, &day(270));
+        assert_eq!(picked, vec![Harvested::Banana(Banana { ripen: true })]);
+        assert!(garden.plot("south").unwrap().get(0, 0).is_none());
+        // the leafy crops spoiled long ago and stay in the ground
+        assert_eq!(spoiled_on(&garden, &day(270)).len(), 2);
+    }
+
+    #[test]
+    fn plots_can_not_share_a_name() {
+        let mut garden = sample_garden();
+        // an unripe spinach in north, a second "north" with a ripe one in the same cell
+        let mut other = Plot::new("north", 1, 1);
+        other.plant(0, 0, Crop::Leafy(Leafy::Spinach), day(-100)).unwrap();
+        assert_eq!(garden.add_plot(other), Err(GardenError::DuplicatePlot("north".to_string())));
+        assert_eq!(garden.plots().len(), 2);
+
+        // so harvesting can't pick the unripe spinach of the first north for the ripe one of the second
+        assert!(harvest(&mut garden, &day(0)).is_empty());
+        assert!(garden.plot("north").unwrap().get(0, 0).is_some());
+    }
+}
@@ -0,0 +1,173 @@
+// a sub module of garden, a plot is a grid of cells where each cell holds at most one planting
+use std::fmt;
+
+use super::date::Date;
+use super::vegetable::{Banana, Leafy, Maturity, Ripeness};
+
+// what grows in a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crop {
+    Leafy(Leafy),
+    Banana,
+}
+
+impl Crop {
+    pub fn maturity(&self) -> Maturity {
+        match self {
+            Crop::Leafy(leafy) => leafy.maturity(),
+            Crop::Banana => Banana::MATURITY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Planting {
+    pub crop: Crop,
+    pub planted_on: Date,
+}
+
+impl Planting {
+    pub fn new(crop: Crop, planted_on: Date) -> Planting {
+        Planting { crop, planted_on }
+    }
+
+    // None before the planting date, otherwise the state after the elapsed days
+    pub fn ripeness_on(&self, date: &Date) -> Option<Ripeness> {
+        let elapsed = u32::try_from(date.days_since(&self.planted_on)).ok()?;
+        Some(Ripeness::Unripe.advance(elapsed, &self.crop.maturity()))
+    }
+
+    // the first day the crop is ripe
+    pub fn ripe_on(&self) -> Date {
+        self.planted_on.add_days(self.crop.maturity().ripe_after as i64)
+    }
+
+    // the first day the crop is overripe
+    pub fn overripe_on(&self) -> Date {
+        self.planted_on.add_days(self.crop.maturity().overripe_after as i64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotError {
+    // the row or column is outside the grid
+    OutOfBounds { row: usize, col: usize },
+    // the cell already has a planting
+    Occupied { row: usize, col: usize },
+}
+
+impl fmt::Display for PlotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlotError::OutOfBounds { row, col } => write!(f, "cell ({}, {}) is outside the plot", row, col),
+            PlotError::Occupied { row, col } => write!(f, "cell ({}, {}) is already planted", row, col),
+        }
+    }
+}
+
+impl std::error::Error for PlotError {}
+
+// the cells are stored row by row in a single Vec, cell (row, col) is at index row * cols + col
+#[derive(Debug, Clone)]
+pub struct Plot {
+    name: String,
+    rows: usize,
+    cols: usize,
+    cells: Vec<Option<Planting>>,
+}
+
+impl Plot {
+    pub fn new(name: &str, rows: usize, cols: usize) -> Plot {
+        Plot {
+            name: name.to_string(),
+            rows,
+            cols,
+            cells: vec![None; rows * cols],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn index(&self, row: usize, col: usize) -> Result<usize, PlotError> {
+        if row >= self.rows || col >= self.cols {
+            return Err(PlotError::OutOfBounds { row, col });
+        }
+        Ok(row * self.cols + col)
+    }
+
+    pub fn plant(&mut self, row: usize, col: usize, crop: Crop, planted_on: Date) -> Result<(), PlotError> {
+        let index = self.index(row, col)?;
+        if self.cells[index].is_some() {
+            return Err(PlotError::Occupied { row, col });
+        }
+        self.cells[index] = Some(Planting::new(crop, planted_on));
+        Ok(())
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&Planting> {
+        let index = self.index(row, col).ok()?;
+        self.cells[index].as_ref()
+    }
+
+    // empties the cell and gives back what was planted there
+    pub fn remove(&mut self, row: usize, col: usize) -> Option<Planting> {
+        let index = self.index(row, col).ok()?;
+        self.cells[index].take()
+    }
+
+    // every planting with its (row, col), in row by row order
+    pub fn plantings(&self) -> impl Iterator<Item = ((usize, usize), &Planting)> + '_ {
+        self.cells.iter().enumerate().filter_map(move |(index, cell)| {
+            cell.as_ref().map(|planting| ((index / self.cols, index % self.cols), planting))
+        })
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(n: i64) -> Date {
+        Date::new(2024, 1, 1).unwrap().add_days(n)
+    }
+
+    #[test]
+    fn planting_validates_cells() {
+        let mut plot = Plot::new("north", 2, 3);
+        assert_eq!(plot.plant(1, 2, Crop::Banana, day(0)), Ok(()));
+        assert_eq!(
+            plot.plant(1, 2, Crop::Leafy(Leafy::Spinach), day(0)),
+            Err(PlotError::Occupied { row: 1, col: 2 })
+        );
+        assert_eq!(plot.plant(2, 0, Crop::Banana, day(0)), Err(PlotError::OutOfBounds { row: 2, col: 0 }));
+
+        plot.plant(0, 1, Crop::Leafy(Leafy::Cabbage), day(3)).unwrap();
+        let cells: Vec<_> = plot.plantings().map(|(cell, _)| cell).collect();
+        assert_eq!(cells, vec![(0, 1), (1, 2)]);
+        assert_eq!(plot.remove(1, 2).map(|p| p.crop), Some(Crop::Banana));
+        assert!(plot.get(1, 2).is_none());
+    }
+
+    #[test]
+    fn ripeness_depends_on_the_date() {
+        let planting = Planting::new(Crop::Leafy(Leafy::Coriander), day(10));
+        assert_eq!(planting.ripeness_on(&day(9)), None);
+        assert_eq!(planting.ripeness_on(&day(10)), Some(Ripeness::Unripe));
+        assert_eq!(planting.ripeness_on(&planting.ripe_on()), Some(Ripeness::Ripe));
+        assert_eq!(planting.ripeness_on(&planting.overripe_on()), Some(Ripeness::Overripe));
+    }
+}
@@ -6,9 +6,11 @@ pub fn tomato(){
 
 // a struct inside a sub-module
 // for each data in struct we need to mention if its public or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Banana {pub ripen: bool} // pub ripen implies, we can edit ripen in main.rs
 
 impl Banana{
+    #[allow(clippy::needless_arbitrary_self_type)]
     pub fn is_banana_ripen(self: &Self){ // it imples slef is referencinf to Banana
         if self.ripen{
             println!("Banana is Riped\n");
@@ -16,10 +18,99 @@ impl Banana{
     }
 }
 
+// Banana plants take about 9 months to fruit and the bunch spoils two weeks later
+impl Banana{
+    pub const MATURITY: Maturity = Maturity{ripe_after: 270, overripe_after: 284};
+}
+
+// a banana is ripen once its state machine has left Unripe
+impl From<Ripeness> for Banana{
+    fn from(ripeness: Ripeness) -> Banana{
+        Banana{ripen: ripeness != Ripeness::Unripe}
+    }
+}
+
 // if the enum is public, all of its variants are public
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Leafy{
     Spinach,
     Cabbage,
     Coriander
 }
 
+impl Leafy{
+    // days after planting until each leafy vegetable is ripe and then overripe
+    pub fn maturity(&self) -> Maturity{
+        match self{
+            Leafy::Spinach => Maturity{ripe_after: 40, overripe_after: 55},
+            Leafy::Cabbage => Maturity{ripe_after: 70, overripe_after: 100},
+            Leafy::Coriander => Maturity{ripe_after: 30, overripe_after: 40},
+        }
+    }
+}
+
+// ------ Ripeness State Machine ------
+// a crop only ever moves forward: Unripe -> Ripe -> Overripe
+// the order of the variants matters, the derived Ord says Unripe < Ripe < Overripe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ripeness{
+    Unripe,
+    Ripe,
+    Overripe,
+}
+
+// how many days after planting a crop becomes ripe and then overripe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Maturity{
+    pub ripe_after: u32,
+    pub overripe_after: u32,
+}
+
+impl Ripeness{
+    // the state that follows this one, Overripe stays Overripe
+    pub fn next(self) -> Ripeness{
+        match self{
+            Ripeness::Unripe => Ripeness::Ripe,
+            Ripeness::Ripe | Ripeness::Overripe => Ripeness::Overripe,
+        }
+    }
+
+    // moves through the states until the elapsed days are below the next threshold
+    // the state never goes backwards, even if fewer days are passed in later
+    pub fn advance(self, elapsed_days: u32, maturity: &Maturity) -> Ripeness{
+        let mut state = self;
+        loop{
+            let threshold = match state{
+                Ripeness::Unripe => maturity.ripe_after,
+                Ripeness::Ripe => maturity.overripe_after,
+                Ripeness::Overripe => return state,
+            };
+            if elapsed_days < threshold{
+                return state;
+            }
+            state = state.next();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn ripeness_follows_elapsed_days(){
+        let maturity = Leafy::Spinach.maturity();
+        assert_eq!(Ripeness::Unripe.advance(39, &maturity), Ripeness::Unripe);
+        assert_eq!(Ripeness::Unripe.advance(40, &maturity), Ripeness::Ripe);
+        assert_eq!(Ripeness::Unripe.advance(55, &maturity), Ripeness::Overripe);
+        // never goes backwards
+        assert_eq!(Ripeness::Overripe.advance(0, &maturity), Ripeness::Overripe);
+    }
+
+    #[test]
+    fn banana_from_ripeness(){
+        assert!(!Banana::from(Ripeness::Unripe).ripen);
+        assert!(Banana::from(Ripeness::Unripe.advance(270, &Banana::MATURITY)).ripen);
+    }
+}
+
@@ -1,2 +1,51 @@
 // This is a library file leading to a library crate
-// here also you can define functions to be used elsewhere
\ No newline at end of file
+// here also you can define functions to be used elsewhere
+// the library crate has the same name as the package, so other crates (and our own main.rs)
+// reach the code below with paths starting with modules_and_crates::, eg: modules_and_crates::war::launc_attack()
+
+// Using a module
+pub mod garden; // here adding pub tells the compiler to include code it finds in this module
+// to use the code within the module, the compiler will look for
+// a file src/graden.rs or src/garden/mod.rs which contains the module's code
+// these are two way to create modules, another way is to create the module in-line as below
+
+// animals registry, the compiler looks for src/animals.rs
+pub mod animals;
+
+// importing war module, the code lives in src/war/mod.rs
+pub mod war;
+
+// a tool that reports the visibility of every item in a crate, see src/bin/visibility_audit.rs
+pub mod audit;
+
+// ------ Nested Modules ----------
+// the below is called in-line module, where module and it's code are defined
+// in the file where it's used, useful when grouping similar functions
+pub mod parent{ // needs pub now, as the binary crate (main.rs) is outside this crate
+    fn parent_fn(){ // private, only parent and its children (child) can call it
+        println!("This is parent function\n")
+    }
+
+    pub mod child{ // requires "pub" as its inside a module
+        pub fn child_fn(){
+            // calling parent fn in child fn using "super"
+            println!("\nInside child");
+            super::parent_fn();
+        }           
+    }
+}
+
+// ------ Prelude ----------
+// a prelude re-exports the items most users need, so they can be imported with one line
+// use modules_and_crates::prelude::*;
+// "pub use" makes the item available at the new path as well as its original path
+pub mod prelude{
+    pub use crate::animals::{AnimalRegistry, Says, Sound};
+    pub use crate::garden::date::Date;
+    pub use crate::garden::plot::{Crop, Plot};
+    pub use crate::garden::vegetable::{self, Banana, Leafy, Ripeness};
+    pub use crate::garden::{garden_name, Garden};
+    pub use crate::parent::child::child_fn;
+    pub use crate::war::battleship::{Board, Coord, Game, Player, ShotResult};
+    pub use crate::war::launc_attack;
+}
@@ -21,56 +21,44 @@
 
 #![allow(dead_code)]
 
-// Using a module
-pub mod garden; // here adding pub tells the compiler to include code it finds in this module
-// to use the code within the module, the compiler will look for
-// a file src/graden.rs or src/garden/mod.rs which contains the module's code
-// these are two way to create modules, another way is to create the module in main.rs
+// The module tree (garden, animals, war, parent) lives in the library crate, see src/lib.rs
+// main.rs is a thin binary crate that uses the library through its public paths
+// here modules_and_crates is the name of the library crate (same as the package name)
 
 // the below is called in-line module, where module and it's code are defined
-// in the file where it's used, useful when grouping similar functions
+// in the file where it's used, this one stays in the binary crate
 mod module_in_main{
     fn this_module_fn_is_in_main() {} // this is private function as no "pub"
 }
 
-// Example of inline module - Animal module containing animal function
-// pub -> imples the fn is public and can be called by any file
-mod animals{
-    pub fn say_meow() {println!("Meow")}
-    pub fn say_woof() {println!("Woof")}
-}
-
 // Using the "use" statement to reduce the lines of code
-use garden::vegetable;
+use modules_and_crates::{animals, garden, parent, war};
+use modules_and_crates::garden::vegetable;
 // now to call the functions/code within the vegetable module, we need not use
 // garden::vegetable::fn_name, instead we can use vegetable::fn_name
 
 // garden::vegetable::fn_name, with this we can directly use fn_name without vegetable
 
 // importing a struct from a sub-module
-use garden::vegetable::Banana;
+use modules_and_crates::garden::vegetable::Banana;
 
-// importing war module
-mod war;
+// the prelude re-exports the common items, so the line below imports all of them
+// use modules_and_crates::prelude::*;
 
-// ------ Nested Modules ----------
-mod parent{ // no need to use pub as we have created this module in file where we use it
-    fn parent_fn(){
-        println!("This is parent function\n")
+fn main() {
+    // ------ animals subcommand ------
+    // cargo run -- animals list        -> prints every registered animal
+    // cargo run -- animals speak cat   -> prints the sound of one animal
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("animals"){
+        std::process::exit(animals_command(&args[1..]));
     }
 
-    pub mod child{ // requires "pub" as its inside a module
-        pub fn child_fn(){
-            // calling parent fn in child fn using "super"
-            println!("\nInside child");
-            super::parent_fn();
-        }           
+    // using the animal registry from the animals module
+    let registry = animals::AnimalRegistry::with_defaults();
+    if let Some(sound) = registry.speak("cat"){
+        println!("{}",sound);
     }
-}
-
-fn main() {
-    // using in-line module functions
-    animals::say_meow();
     // module_in_main::this_module_fn_is_in_main(); error as its private fn
 
     // running a code from module
@@ -85,7 +73,12 @@ fn main() {
     //println!("Is my Banana Ripen? \nAns: {}\n",my_banana.ripen);
 
     // using functions from war/mod.rs
-    war::launc_attack();
+    // launc_attack() fires at a board from the war::battleship sub-module and returns the result
+    let mut enemy_board = war::battleship::Board::new();
+    match war::launc_attack(&mut enemy_board, 3, 7){
+        Ok(result) => println!("Missile Launched: {:?}\n",result),
+        Err(error) => println!("Missile not launched: {}\n",error),
+    }
 
     // calling parent code from a child module
     parent::child::child_fn();
@@ -94,15 +87,43 @@ fn main() {
 }
 
 
+// returns the exit code, 0 when it worked and 2 for wrong usage
+fn animals_command(args: &[String]) -> i32{
+    let registry = animals::AnimalRegistry::with_defaults();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice(){
+        ["list"] => {
+            for name in registry.names(){
+                println!("{}",name);
+            }
+            0
+        }
+        ["speak", name] => match registry.speak(name){
+            Some(sound) => {
+                println!("{}",sound);
+                0
+            }
+            None => {
+                eprintln!("unknown animal {:?}, try: animals list",name);
+                2
+            }
+        },
+        _ => {
+            eprintln!("usage: animals list | animals speak NAME");
+            2
+        }
+    }
+}
+
 // Absolute path
 // An absolute path is the full path starting from a crate root; for code from an 
 // external crate, the absolute path begins with the crate name, and for code from 
 // the current crate, it starts with the literal crate.
-// crate::animals::say_meow();
-// the above is an abosolute path, where the crate gets resolved to our project name
+// crate::animals::AnimalRegistry::new(); -> inside lib.rs
+// modules_and_crates::animals::AnimalRegistry::new(); -> from main.rs or any other crate
+// the above are abosolute paths, where the crate gets resolved to our project name
 
 // Relative path
-// animals::say_meow();
+// animals::AnimalRegistry::new();
 
 
 // ----- USE Statement ------
@@ -0,0 +1,178 @@
+// a computer opponent using the "hunt and target" strategy
+// hunt   -> fire at random cells on a checkerboard pattern, every ship covers at least one of them
+// target -> after a hit, fire at the cells around it until the ship is sunk
+use super::board::Board;
+use super::{Coord, ShotResult};
+
+// a small seeded random number generator (SplitMix64), the same seed gives the same numbers
+// so games against the AI can be replayed in tests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // a number in 0..n, n must not be 0
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ai {
+    rng: Rng,
+    // cells next to earlier hits, the last one is tried first
+    targets: Vec<Coord>,
+    // hits on ships that are not sunk yet
+    hits: Vec<Coord>,
+}
+
+impl Ai {
+    pub fn new(seed: u64) -> Ai {
+        Ai { rng: Rng::new(seed), targets: Vec::new(), hits: Vec::new() }
+    }
+
+    // picks the next cell to fire at on the opponent's board
+    // only which cells were already fired at is looked at, never where the ships are
+    pub fn choose(&mut self, opponent: &Board) -> Coord {
+        while let Some(target) = self.targets.pop() {
+            if !opponent.is_fired(&target) {
+                return target;
+            }
+        }
+
+        let open: Vec<Coord> = Coord::all().filter(|cell| !opponent.is_fired(cell)).collect();
+        let checkerboard: Vec<Coord> = open.iter().copied().filter(|cell| (cell.row + cell.col) % 2 == 0).collect();
+        let candidates = if checkerboard.is_empty() { &open } else { &checkerboard };
+        candidates[self.rng.below(candidates.len() as u64) as usize]
+    }
+
+    // tells the AI what its last shot did, so it can switch between hunting and targeting
+    pub fn record(&mut self, at: Coord, result: ShotResult) {
+        match result {
+            ShotResult::Miss => (),
+            ShotResult::Hit => {
+                self.hits.push(at);
+                self.targets.extend(at.neighbours());
+            }
+            // the ship is gone, stop searching around its cells
+            // but keep searching around hits on other ships that are still afloat
+            ShotResult::Sunk(kind) => {
+                self.hits.push(at);
+                for cell in self.sunk_cells(at, kind.size()) {
+                    self.hits.retain(|hit| *hit != cell);
+                }
+                self.targets = self.hits.iter().flat_map(|hit| hit.neighbours()).collect();
+            }
+        }
+    }
+
+    // the cells of the ship that was sunk at `at`: a row or column of `size` hits going through it
+    // when no such line is found only `at` is known to belong to the ship
+    fn sunk_cells(&self, at: Coord, size: u8) -> Vec<Coord> {
+        let size = size as i8;
+        for (rows, cols) in [(0, 1), (1, 0)] {
+            for first in (1 - size)..=0 {
+                let line: Option<Vec<Coord>> =
+                    (first..first + size).map(|step| at.offset(rows * step, cols * step)).collect();
+                if let Some(line) = line.filter(|line| line.iter().all(|cell| self.hits.contains(cell))) {
+                    return line;
+                }
+            }
+        }
+        vec![at]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::war::battleship::{Game, Orientation, Player, ShipKind};
+
+    // plays a whole game of AI against AI and returns the winner and number of shots
+    fn play(seed: u64) -> (Player, usize) {
+        let mut rng = Rng::new(seed);
+        let mut game = Game::new(Board::random_fleet(&mut rng), Board::random_fleet(&mut rng)).unwrap();
+        let mut ais = [Ai::new(seed + 1), Ai::new(seed + 2)];
+        let mut shots = 0;
+        while game.winner().is_none() {
+            let player = game.turn();
+            let ai = &mut ais[if player == Player::One { 0 } else { 1 }];
+            let at = ai.choose(game.board(player.other()));
+            let result = game.fire(player, at).unwrap();
+            ai.record(at, result);
+            shots += 1;
+        }
+        (game.winner().unwrap(), shots)
+    }
+
+    #[test]
+    fn games_are_repeatable_and_finish() {
+        for seed in 0..20 {
+            let (winner, shots) = play(seed);
+            assert_eq!(play(seed), (winner, shots));
+            assert!(shots <= 200);
+        }
+    }
+
+    #[test]
+    fn targets_around_a_hit() {
+        let mut board = Board::new();
+        board.place(ShipKind::Carrier, Coord::new(5, 2), Orientation::Horizontal).unwrap();
+        let mut ai = Ai::new(3);
+        let hit = Coord::new(5, 4);
+        ai.record(hit, board.fire(hit).unwrap());
+
+        // the next shot is next to the hit, and the carrier goes down without hunting again
+        let at = ai.choose(&board);
+        assert!(hit.neighbours().any(|cell| cell == at));
+        ai.record(at, board.fire(at).unwrap());
+
+        let mut shots = 1;
+        while !board.all_sunk() {
+            let at = ai.choose(&board);
+            ai.record(at, board.fire(at).unwrap());
+            shots += 1;
+        }
+        assert!(shots <= 16, "took {} shots", shots);
+    }
+
+    #[test]
+    fn keeps_targeting_a_ship_next_to_a_sunk_one() {
+        // the destroyer lies right on top of the cruiser
+        let mut board = Board::new();
+        board.place(ShipKind::Destroyer, Coord::new(3, 3), Orientation::Horizontal).unwrap();
+        board.place(ShipKind::Cruiser, Coord::new(4, 3), Orientation::Horizontal).unwrap();
+        let mut ai = Ai::new(5);
+        for at in [Coord::new(4, 4), Coord::new(3, 4)] {
+            assert_eq!(board.fire(at), Ok(ShotResult::Hit));
+            ai.record(at, ShotResult::Hit);
+        }
+        let at = Coord::new(3, 3);
+        assert_eq!(board.fire(at), Ok(ShotResult::Sunk(ShipKind::Destroyer)));
+        ai.record(at, ShotResult::Sunk(ShipKind::Destroyer));
+
+        // the hit on the cruiser is still chased, the next shot is next to it
+        let at = ai.choose(&board);
+        assert!(Coord::new(4, 4).neighbours().any(|cell| cell == at), "fired at {:?}", at);
+        ai.record(at, board.fire(at).unwrap());
+
+        let mut shots = 1;
+        while !board.all_sunk() {
+            let at = ai.choose(&board);
+            ai.record(at, board.fire(at).unwrap());
+            shots += 1;
+        }
+        assert!(shots <= 6, "took {} shots", shots);
+    }
+}
@@ -0,0 +1,182 @@
+// one player's side of the game: where the ships are and which cells were fired at
+use super::ai::Rng;
+use super::{Coord, Orientation, PlacementError, ShipKind, ShotError, ShotResult, BOARD_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ship {
+    pub kind: ShipKind,
+    pub start: Coord,
+    pub orientation: Orientation,
+}
+
+impl Ship {
+    // the cells covered by the ship, None if any of them is off the board
+    pub fn cells(&self) -> Option<Vec<Coord>> {
+        (0..self.kind.size() as i8)
+            .map(|step| match self.orientation {
+                Orientation::Horizontal => self.start.offset(0, step),
+                Orientation::Vertical => self.start.offset(step, 0),
+            })
+            .collect()
+    }
+
+    pub fn covers(&self, at: &Coord) -> bool {
+        let (along, across, start_along, start_across) = match self.orientation {
+            Orientation::Horizontal => (at.col, at.row, self.start.col, self.start.row),
+            Orientation::Vertical => (at.row, at.col, self.start.row, self.start.col),
+        };
+        across == start_across && along >= start_along && along < start_along + self.kind.size()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    ships: Vec<Ship>,
+    // fired[row][col] is true once that cell was shot at
+    fired: [[bool; BOARD_SIZE as usize]; BOARD_SIZE as usize],
+    // the same shots in the order they were fired, used for replays and saving
+    shots: Vec<Coord>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
+
+impl Board {
+    pub fn new() -> Board {
+        Board {
+            ships: Vec::new(),
+            fired: [[false; BOARD_SIZE as usize]; BOARD_SIZE as usize],
+            shots: Vec::new(),
+        }
+    }
+
+    pub fn place(&mut self, kind: ShipKind, start: Coord, orientation: Orientation) -> Result<(), PlacementError> {
+        if self.ships.iter().any(|ship| ship.kind == kind) {
+            return Err(PlacementError::AlreadyPlaced(kind));
+        }
+        let ship = Ship { kind, start, orientation };
+        let cells = ship.cells().ok_or(PlacementError::OutOfBounds)?;
+        for cell in &cells {
+            if let Some(other) = self.ship_at(cell) {
+                return Err(PlacementError::Overlap(other.kind));
+            }
+        }
+        self.ships.push(ship);
+        Ok(())
+    }
+
+    // places the whole fleet at random, the same seed always gives the same layout
+    pub fn random_fleet(rng: &mut Rng) -> Board {
+        let mut board = Board::new();
+        for kind in ShipKind::FLEET {
+            loop {
+                let start = Coord::new(rng.below(BOARD_SIZE as u64) as u8, rng.below(BOARD_SIZE as u64) as u8);
+                let orientation = if rng.below(2) == 0 { Orientation::Horizontal } else { Orientation::Vertical };
+                if board.place(kind, start, orientation).is_ok() {
+                    break;
+                }
+            }
+        }
+        board
+    }
+
+    pub fn ships(&self) -> &[Ship] {
+        &self.ships
+    }
+
+    pub fn ship_at(&self, at: &Coord) -> Option<&Ship> {
+        self.ships.iter().find(|ship| ship.covers(at))
+    }
+
+    pub fn is_fleet_complete(&self) -> bool {
+        ShipKind::FLEET.iter().all(|kind| self.ships.iter().any(|ship| ship.kind == *kind))
+    }
+
+    pub fn is_fired(&self, at: &Coord) -> bool {
+        at.is_on_board() && self.fired[at.row as usize][at.col as usize]
+    }
+
+    // the shots received so far, oldest first
+    pub fn shots(&self) -> &[Coord] {
+        &self.shots
+    }
+
+    pub fn is_sunk(&self, ship: &Ship) -> bool {
+        ship.cells().unwrap_or_default().iter().all(|cell| self.is_fired(cell))
+    }
+
+    pub fn all_sunk(&self) -> bool {
+        !self.ships.is_empty() && self.ships.iter().all(|ship| self.is_sunk(ship))
+    }
+
+    // resolves a shot at this board
+    pub fn fire(&mut self, at: Coord) -> Result<ShotResult, ShotError> {
+        if !at.is_on_board() {
+            return Err(ShotError::OutOfBounds(at));
+        }
+        if self.is_fired(&at) {
+            return Err(ShotError::AlreadyFired(at));
+        }
+        self.fired[at.row as usize][at.col as usize] = true;
+        self.shots.push(at);
+
+        match self.ship_at(&at) {
+            None => Ok(ShotResult::Miss),
+            Some(ship) if self.is_sunk(ship) => Ok(ShotResult::Sunk(ship.kind)),
+            Some(_) => Ok(ShotResult::Hit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_is_validated() {
+        let mut board = Board::new();
+        let start = Coord::new(0, 0);
+        assert_eq!(board.place(ShipKind::Carrier, start, Orientation::Horizontal), Ok(()));
+        assert_eq!(
+            board.place(ShipKind::Carrier, Coord::new(5, 5), Orientation::Vertical),
+            Err(PlacementError::AlreadyPlaced(ShipKind::Carrier))
+        );
+        assert_eq!(
+            board.place(ShipKind::Cruiser, Coord::new(0, 4), Orientation::Vertical),
+            Err(PlacementError::Overlap(ShipKind::Carrier))
+        );
+        assert_eq!(
+            board.place(ShipKind::Battleship, Coord::new(7, 0), Orientation::Vertical),
+            Err(PlacementError::OutOfBounds)
+        );
+        // touching is allowed, only overlapping is not
+        assert_eq!(board.place(ShipKind::Cruiser, Coord::new(0, 5), Orientation::Vertical), Ok(()));
+        assert!(!board.is_fleet_complete());
+    }
+
+    #[test]
+    fn shots_miss_hit_and_sink() {
+        let mut board = Board::new();
+        board.place(ShipKind::Destroyer, Coord::new(3, 3), Orientation::Vertical).unwrap();
+
+        assert_eq!(board.fire(Coord::new(0, 0)), Ok(ShotResult::Miss));
+        assert_eq!(board.fire(Coord::new(3, 3)), Ok(ShotResult::Hit));
+        assert_eq!(board.fire(Coord::new(3, 3)), Err(ShotError::AlreadyFired(Coord::new(3, 3))));
+        assert_eq!(board.fire(Coord::new(10, 0)), Err(ShotError::OutOfBounds(Coord::new(10, 0))));
+        assert_eq!(board.fire(Coord::new(4, 3)), Ok(ShotResult::Sunk(ShipKind::Destroyer)));
+        assert!(board.all_sunk());
+        assert_eq!(board.shots().len(), 3);
+    }
+
+    #[test]
+    fn random_fleet_is_complete_and_repeatable() {
+        let board = Board::random_fleet(&mut Rng::new(7));
+        assert!(board.is_fleet_complete());
+        let covered: usize = board.ships().iter().map(|ship| ship.kind.size() as usize).sum();
+        assert_eq!(Coord::all().filter(|cell| board.ship_at(cell).is_some()).count(), covered);
+        assert_eq!(board, Board::random_fleet(&mut Rng::new(7)));
+    }
+}
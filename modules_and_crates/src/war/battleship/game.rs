@@ -0,0 +1,122 @@
+// two boards and the turn order, players take turns firing one shot each
+use super::board::Board;
+use super::{Coord, PlacementError, ShotError, ShotResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn other(&self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    // boards[0] belongs to Player::One, boards[1] to Player::Two
+    boards: [Board; 2],
+    turn: Player,
+    winner: Option<Player>,
+}
+
+impl Game {
+    // both boards need the whole fleet, Player::One fires first
+    pub fn new(one: Board, two: Board) -> Result<Game, PlacementError> {
+        if !one.is_fleet_complete() || !two.is_fleet_complete() {
+            return Err(PlacementError::FleetIncomplete);
+        }
+        Ok(Game {
+            boards: [one, two],
+            turn: Player::One,
+            winner: None,
+        })
+    }
+
+    // the board with the player's own ships
+    pub fn board(&self, player: Player) -> &Board {
+        &self.boards[player.index()]
+    }
+
+    pub fn turn(&self) -> Player {
+        self.turn
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        self.winner
+    }
+
+    // "player" fires at the other player's board, then the turn passes to the other player
+    // a rejected shot (off the board, fired twice) does not use up the turn
+    pub fn fire(&mut self, player: Player, at: Coord) -> Result<ShotResult, ShotError> {
+        if self.winner.is_some() {
+            return Err(ShotError::GameOver);
+        }
+        if player != self.turn {
+            return Err(ShotError::NotYourTurn);
+        }
+
+        let target = &mut self.boards[player.other().index()];
+        let result = target.fire(at)?;
+        if target.all_sunk() {
+            self.winner = Some(player);
+        } else {
+            self.turn = player.other();
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::war::battleship::{Orientation, ShipKind};
+
+    // the fleet stacked in the first five rows, each ship starting at column 0
+    fn stacked_fleet() -> Board {
+        let mut board = Board::new();
+        for (row, kind) in ShipKind::FLEET.into_iter().enumerate() {
+            board.place(kind, Coord::new(row as u8, 0), Orientation::Horizontal).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn needs_complete_fleets() {
+        assert_eq!(Game::new(Board::new(), stacked_fleet()), Err(PlacementError::FleetIncomplete));
+    }
+
+    #[test]
+    fn turns_alternate_until_someone_wins() {
+        let mut game = Game::new(stacked_fleet(), stacked_fleet()).unwrap();
+        assert_eq!(game.fire(Player::Two, Coord::new(0, 0)), Err(ShotError::NotYourTurn));
+        assert_eq!(game.fire(Player::One, Coord::new(20, 0)), Err(ShotError::OutOfBounds(Coord::new(20, 0))));
+        assert_eq!(game.turn(), Player::One);
+
+        // player one hits every ship cell, player two always fires at the empty bottom rows
+        let targets: Vec<Coord> = Coord::all().filter(|cell| game.board(Player::Two).ship_at(cell).is_some()).collect();
+        let mut misses = Coord::all().filter(|cell| cell.row >= 5);
+        let mut last = None;
+        for target in targets {
+            last = Some(game.fire(Player::One, target).unwrap());
+            if game.winner().is_none() {
+                assert_eq!(game.fire(Player::Two, misses.next().unwrap()), Ok(ShotResult::Miss));
+            }
+        }
+        assert_eq!(last, Some(ShotResult::Sunk(ShipKind::Destroyer)));
+        assert_eq!(game.winner(), Some(Player::One));
+        assert_eq!(game.fire(Player::Two, Coord::new(9, 9)), Err(ShotError::GameOver));
+    }
+}
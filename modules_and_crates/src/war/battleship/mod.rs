@@ -1 +1,230 @@
-// battleship is a sub-module of war
\ No newline at end of file
+// battleship is a sub-module of war
+// it holds a two player Battleship engine, split into sub-modules of its own:
+// board.rs -> one player's ships and the shots fired at them
+// game.rs  -> two boards and whose turn it is
+// ai.rs    -> a seeded computer opponent
+// save.rs  -> a text format for saving and loading a game in progress
+use std::fmt;
+use std::str::FromStr;
+
+pub mod ai;
+pub mod board;
+pub mod game;
+pub mod save;
+
+// re-exporting, so users can write battleship::Board instead of battleship::board::Board
+pub use ai::{Ai, Rng};
+pub use board::{Board, Ship};
+pub use game::{Game, Player};
+
+// the board is BOARD_SIZE x BOARD_SIZE cells
+pub const BOARD_SIZE: u8 = 10;
+
+// a cell on the board, row and col both start at 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub row: u8,
+    pub col: u8,
+}
+
+impl Coord {
+    pub fn new(row: u8, col: u8) -> Coord {
+        Coord { row, col }
+    }
+
+    pub fn is_on_board(&self) -> bool {
+        self.row < BOARD_SIZE && self.col < BOARD_SIZE
+    }
+
+    // the cell moved by (rows, cols), None if it would leave the board
+    pub fn offset(&self, rows: i8, cols: i8) -> Option<Coord> {
+        let row = u8::try_from(self.row as i16 + rows as i16).ok()?;
+        let col = u8::try_from(self.col as i16 + cols as i16).ok()?;
+        Some(Coord { row, col }).filter(Coord::is_on_board)
+    }
+
+    // the cells above, below, left and right that are on the board
+    pub fn neighbours(&self) -> impl Iterator<Item = Coord> + '_ {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter_map(move |(rows, cols)| self.offset(rows, cols))
+    }
+
+    // every cell of the board, row by row
+    pub fn all() -> impl Iterator<Item = Coord> {
+        (0..BOARD_SIZE).flat_map(|row| (0..BOARD_SIZE).map(move |col| Coord { row, col }))
+    }
+}
+
+// players name cells with a row letter and a column number, eg: "B7" -> row 1, col 6
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", (b'A' + self.row) as char, self.col + 1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCoordError {
+    Empty,
+    // the first character is not a row letter between A and the last row
+    BadRow(char),
+    // what follows the row letter is not a column number between 1 and BOARD_SIZE
+    BadColumn(String),
+}
+
+impl fmt::Display for ParseCoordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let last_row = (b'A' + BOARD_SIZE - 1) as char;
+        match self {
+            ParseCoordError::Empty => write!(f, "enter a cell like B7"),
+            ParseCoordError::BadRow(c) => write!(f, "row {:?} is not between A and {}", c, last_row),
+            ParseCoordError::BadColumn(col) => write!(f, "column {:?} is not between 1 and {}", col, BOARD_SIZE),
+        }
+    }
+}
+
+impl std::error::Error for ParseCoordError {}
+
+// "b7", "B7" and " B 7 " are all accepted
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or(ParseCoordError::Empty)?;
+        let row = match letter.to_ascii_uppercase() {
+            c @ 'A'..='Z' if (c as u8 - b'A') < BOARD_SIZE => c as u8 - b'A',
+            _ => return Err(ParseCoordError::BadRow(letter)),
+        };
+        let rest = chars.as_str().trim();
+        let col = match rest.parse::<u8>() {
+            Ok(col) if (1..=BOARD_SIZE).contains(&col) => col - 1,
+            _ => return Err(ParseCoordError::BadColumn(rest.to_string())),
+        };
+        Ok(Coord { row, col })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Horizontal, // the ship grows to the right of its start cell
+    Vertical,   // the ship grows downwards from its start cell
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShipKind {
+    Carrier,
+    Battleship,
+    Cruiser,
+    Submarine,
+    Destroyer,
+}
+
+impl ShipKind {
+    // the opposite of name(), used when loading saved games
+    pub fn from_name(name: &str) -> Option<ShipKind> {
+        ShipKind::FLEET.into_iter().find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+
+    // every player places exactly one ship of each kind
+    pub const FLEET: [ShipKind; 5] = [
+        ShipKind::Carrier,
+        ShipKind::Battleship,
+        ShipKind::Cruiser,
+        ShipKind::Submarine,
+        ShipKind::Destroyer,
+    ];
+
+    pub fn size(&self) -> u8 {
+        match self {
+            ShipKind::Carrier => 5,
+            ShipKind::Battleship => 4,
+            ShipKind::Cruiser | ShipKind::Submarine => 3,
+            ShipKind::Destroyer => 2,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShipKind::Carrier => "Carrier",
+            ShipKind::Battleship => "Battleship",
+            ShipKind::Cruiser => "Cruiser",
+            ShipKind::Submarine => "Submarine",
+            ShipKind::Destroyer => "Destroyer",
+        }
+    }
+}
+
+// what happened to a shot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShotResult {
+    Miss,
+    Hit,
+    Sunk(ShipKind), // the shot hit the last untouched cell of this ship
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementError {
+    // part of the ship would be outside the board
+    OutOfBounds,
+    // the ship would cover a cell of another ship
+    Overlap(ShipKind),
+    // this kind of ship is already on the board
+    AlreadyPlaced(ShipKind),
+    // a game can only start once both boards have the whole fleet
+    FleetIncomplete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShotError {
+    OutOfBounds(Coord),
+    AlreadyFired(Coord),
+    NotYourTurn,
+    GameOver,
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlacementError::OutOfBounds => write!(f, "ship does not fit on the board"),
+            PlacementError::Overlap(kind) => write!(f, "ship overlaps the {}", kind.name()),
+            PlacementError::AlreadyPlaced(kind) => write!(f, "the {} is already placed", kind.name()),
+            PlacementError::FleetIncomplete => write!(f, "not every ship of the fleet is placed"),
+        }
+    }
+}
+
+impl fmt::Display for ShotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShotError::OutOfBounds(at) => write!(f, "row {} col {} is outside the board", at.row, at.col),
+            ShotError::AlreadyFired(at) => write!(f, "{} was already fired at", at),
+            ShotError::NotYourTurn => write!(f, "it is the other player's turn"),
+            ShotError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for PlacementError {}
+impl std::error::Error for ShotError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coords_parse_and_print() {
+        assert_eq!("B7".parse(), Ok(Coord::new(1, 6)));
+        assert_eq!(" j 10 ".parse(), Ok(Coord::new(9, 9)));
+        assert_eq!(Coord::new(0, 0).to_string(), "A1");
+        assert_eq!("".parse::<Coord>(), Err(ParseCoordError::Empty));
+        assert_eq!("K1".parse::<Coord>(), Err(ParseCoordError::BadRow('K')));
+        assert_eq!("7B".parse::<Coord>(), Err(ParseCoordError::BadRow('7')));
+        assert_eq!("A0".parse::<Coord>(), Err(ParseCoordError::BadColumn("0".to_string())));
+        assert_eq!("A11".parse::<Coord>(), Err(ParseCoordError::BadColumn("11".to_string())));
+        for cell in Coord::all() {
+            assert_eq!(cell.to_string().parse(), Ok(cell));
+        }
+    }
+}
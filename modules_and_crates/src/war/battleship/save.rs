@@ -0,0 +1,167 @@
+// a plain text format for a game in progress, one entry per line:
+//
+// battleship-save 1
+// seed 42
+// ship 1 Carrier A1 H      -> player one's Carrier starts at A1 and is horizontal
+// shot 1 B7                -> player one fired at B7 on player two's board
+//
+// loading places the ships and replays the shots through Game::fire(),
+// so a save file with an impossible game (overlapping ships, shots out of turn) is rejected
+use std::fmt;
+
+use super::board::Board;
+use super::game::{Game, Player};
+use super::{Coord, Orientation, ShipKind};
+
+const HEADER: &str = "battleship-save 1";
+
+// a loaded game and the seed the AI opponent was created with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedGame {
+    pub seed: u64,
+    pub game: Game,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    pub line: usize, // starts at 1, 0 means the problem is not on a single line
+    pub message: String,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+fn player_number(player: Player) -> u8 {
+    match player {
+        Player::One => 1,
+        Player::Two => 2,
+    }
+}
+
+pub fn save(game: &Game, seed: u64) -> String {
+    let mut out = format!("{}\nseed {}\n", HEADER, seed);
+    for player in [Player::One, Player::Two] {
+        for ship in game.board(player).ships() {
+            let orientation = match ship.orientation {
+                Orientation::Horizontal => 'H',
+                Orientation::Vertical => 'V',
+            };
+            out += &format!("ship {} {} {} {}\n", player_number(player), ship.kind.name(), ship.start, orientation);
+        }
+    }
+
+    // player one always fires first and turns alternate, so the shots of both
+    // players are interleaved: one's first shot, two's first shot, one's second shot...
+    let by_one = game.board(Player::Two).shots();
+    let by_two = game.board(Player::One).shots();
+    for index in 0..by_one.len().max(by_two.len()) {
+        if let Some(at) = by_one.get(index) {
+            out += &format!("shot 1 {}\n", at);
+        }
+        if let Some(at) = by_two.get(index) {
+            out += &format!("shot 2 {}\n", at);
+        }
+    }
+    out
+}
+
+pub fn load(text: &str) -> Result<SavedGame, LoadError> {
+    let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+    let error = |line: usize, message: String| LoadError { line, message };
+
+    match lines.next() {
+        Some((_, HEADER)) => (),
+        _ => return Err(error(1, format!("expected {:?}", HEADER))),
+    }
+
+    let mut seed = None;
+    let mut boards = [Board::new(), Board::new()];
+    let mut shots = Vec::new();
+
+    for (number, line) in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => (),
+            ["seed", value] => {
+                seed = Some(value.parse().map_err(|_| error(number, format!("bad seed {:?}", value)))?);
+            }
+            ["ship", player, kind, start, orientation] => {
+                let board = match *player {
+                    "1" => &mut boards[0],
+                    "2" => &mut boards[1],
+                    _ => return Err(error(number, format!("bad player {:?}", player))),
+                };
+                let kind = ShipKind::from_name(kind).ok_or_else(|| error(number, format!("bad ship {:?}", kind)))?;
+                let start: Coord = start.parse().map_err(|e| error(number, format!("{}", e)))?;
+                let orientation = match *orientation {
+                    "H" => Orientation::Horizontal,
+                    "V" => Orientation::Vertical,
+                    _ => return Err(error(number, format!("bad orientation {:?}", orientation))),
+                };
+                board.place(kind, start, orientation).map_err(|e| error(number, e.to_string()))?;
+            }
+            ["shot", player, at] => {
+                let player = match *player {
+                    "1" => Player::One,
+                    "2" => Player::Two,
+                    _ => return Err(error(number, format!("bad player {:?}", player))),
+                };
+                let at: Coord = at.parse().map_err(|e| error(number, format!("{}", e)))?;
+                shots.push((number, player, at));
+            }
+            _ => return Err(error(number, format!("unknown entry {:?}", line))),
+        }
+    }
+
+    let seed = seed.ok_or_else(|| error(0, "missing seed".to_string()))?;
+    let [one, two] = boards;
+    let mut game = Game::new(one, two).map_err(|e| error(0, e.to_string()))?;
+    for (number, player, at) in shots {
+        game.fire(player, at).map_err(|e| error(number, e.to_string()))?;
+    }
+    Ok(SavedGame { seed, game })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::war::battleship::{Ai, Rng};
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut rng = Rng::new(11);
+        let mut game = Game::new(Board::random_fleet(&mut rng), Board::random_fleet(&mut rng)).unwrap();
+        let mut ai = Ai::new(5);
+        for _ in 0..31 {
+            let player = game.turn();
+            let at = ai.choose(game.board(player.other()));
+            game.fire(player, at).unwrap();
+        }
+
+        let text = save(&game, 11);
+        assert_eq!(load(&text), Ok(SavedGame { seed: 11, game: game.clone() }));
+    }
+
+    #[test]
+    fn rejects_broken_saves() {
+        assert_eq!(load("hello").unwrap_err().line, 1);
+
+        let mut rng = Rng::new(2);
+        let game = Game::new(Board::random_fleet(&mut rng), Board::random_fleet(&mut rng)).unwrap();
+        let text = save(&game, 2);
+
+        // player two cannot fire first
+        let out_of_turn = format!("{}shot 2 A1\n", text);
+        let error = load(&out_of_turn).unwrap_err();
+        assert_eq!(error.line, text.lines().count() + 1);
+        assert!(error.message.contains("turn"));
+
+        let missing_ship: String = text.lines().filter(|line| !line.contains("Carrier")).map(|line| format!("{}\n", line)).collect();
+        assert_eq!(load(&missing_ship).unwrap_err().line, 0);
+    }
+}
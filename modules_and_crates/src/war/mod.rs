@@ -1,5 +1,9 @@
 // this file contains the functions/code related to the module "war"
 pub mod battleship;
-pub fn launc_attack(){
-    println!("Missile Launched");
-}
\ No newline at end of file
+
+use battleship::{Board, Coord, ShotError, ShotResult};
+
+// fires a missile at (row, col) of the target board and returns what it hit
+pub fn launc_attack(target: &mut Board, row: u8, col: u8) -> Result<ShotResult, ShotError>{
+    target.fire(Coord::new(row, col))
+}
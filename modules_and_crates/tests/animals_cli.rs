@@ -0,0 +1,15 @@
+// runs the modules_and_crates binary with the animals subcommand
+use std::process::Command;
+
+fn run(args: &[&str]) -> (i32, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_modules_and_crates")).args(args).output().unwrap();
+    (output.status.code().unwrap(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn lists_and_invokes_registered_animals() {
+    assert_eq!(run(&["animals", "list"]), (0, "cat\ndog\n".to_string()));
+    assert_eq!(run(&["animals", "speak", "dog"]), (0, "Woof\n".to_string()));
+    assert_eq!(run(&["animals", "speak", "fox"]), (2, String::new()));
+    assert_eq!(run(&["animals"]).0, 2);
+}
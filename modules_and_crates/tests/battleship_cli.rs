@@ -0,0 +1,86 @@
+// runs the battleship binary in script mode and checks what it prints
+// cargo builds the binaries of the package before the integration tests and
+// tells us where through the CARGO_BIN_EXE_<name> environment variable
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// a directory of its own for each test, removed when the test ends, even when it fails
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(test: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("battleship_{}_{}", std::process::id(), test));
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn file(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_battleship")).args(args).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn script_mode_validates_input() {
+    let dir = TempDir::new("validate");
+    let script = dir.file("validate.txt", "# comments are skipped\nB7\nZ3\nB11\nB7\nfly away\nsave\nload  \n");
+    let output = run(&["--seed", "3", "--script", script.to_str().unwrap()]);
+
+    assert!(output.contains("> B7\nYou fire at B7: "));
+    assert!(output.contains("Invalid cell \"Z3\": row 'Z' is not between A and J"));
+    assert!(output.contains("Invalid cell \"B11\": column \"11\" is not between 1 and 10"));
+    assert!(output.contains("Cannot fire at B7: B7 was already fired at"));
+    assert!(output.contains("Unknown command \"fly away\""));
+    assert!(output.contains("> save\nsave needs a file path, eg: save game.txt"));
+    assert!(output.contains("> load\nload needs a file path, eg: load game.txt"));
+    assert!(!output.contains("comments"));
+}
+
+#[test]
+fn same_seed_replays_the_same_game() {
+    let dir = TempDir::new("replay");
+    let script = dir.file("replay.txt", "A1\nC5\nJ10\n");
+    let path = script.to_str().unwrap();
+    assert_eq!(run(&["--seed", "9", "--script", path]), run(&["--seed", "9", "--script", path]));
+}
+
+#[test]
+fn firing_everywhere_ends_the_game() {
+    let cells: String = "ABCDEFGHIJ".chars().flat_map(|row| (1..=10).map(move |col| format!("{}{}\n", row, col))).collect();
+    let dir = TempDir::new("full");
+    let script = dir.file("full.txt", &cells);
+    let output = run(&["--seed", "1", "--script", script.to_str().unwrap()]);
+    assert!(output.contains("You win!") || output.contains("You lose"));
+}
+
+#[test]
+fn saved_games_can_be_continued() {
+    let dir = TempDir::new("save");
+    // a space in the name, the path is the whole rest of the line
+    let save_path = dir.0.join("my game.save");
+    let save_path = save_path.to_str().unwrap();
+
+    let script = dir.file("save.txt", &format!("A1\nB2\nsave {}\nC3\n", save_path));
+    let straight = run(&["--seed", "5", "--script", script.to_str().unwrap()]);
+    assert!(straight.contains(&format!("Saved to {}", save_path)));
+
+    let rest = dir.file("rest.txt", "C3\n");
+    let continued = run(&["--load", save_path, "--script", rest.to_str().unwrap()]);
+    // the last board printed is the same whether or not the game was saved in between
+    let last_board = |output: &str| output.rsplit("Your fleet").next().unwrap().to_string();
+    assert_eq!(last_board(&straight), last_board(&continued));
+}
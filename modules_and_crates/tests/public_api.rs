@@ -0,0 +1,37 @@
+// Integration tests live in the tests/ folder, each file is compiled as a separate crate
+// so they can only use the public API of the library, just like any other crate would
+use modules_and_crates::garden::vegetable::{self, Banana, Leafy};
+use modules_and_crates::prelude;
+
+#[test]
+fn module_paths_are_public() {
+    modules_and_crates::garden::garden_name();
+    let registry = modules_and_crates::animals::AnimalRegistry::with_defaults();
+    assert_eq!(registry.speak("cat").as_deref(), Some("Meow"));
+    let mut board = modules_and_crates::war::battleship::Board::new();
+    let result = modules_and_crates::war::launc_attack(&mut board, 0, 0);
+    assert_eq!(result, Ok(modules_and_crates::war::battleship::ShotResult::Miss));
+    modules_and_crates::parent::child::child_fn();
+    vegetable::tomato();
+}
+
+#[test]
+fn banana_fields_are_public() {
+    let mut banana = Banana { ripen: false };
+    banana.ripen = true;
+    banana.is_banana_ripen();
+    assert!(banana.ripen);
+}
+
+#[test]
+fn prelude_re_exports_the_same_items() {
+    // a re-export is the same item, so a Banana from the prelude is a garden::vegetable::Banana
+    let banana: Banana = prelude::Banana { ripen: true };
+    assert!(banana.ripen);
+    assert!(matches!(prelude::Leafy::Spinach, Leafy::Spinach));
+    prelude::vegetable::tomato();
+    let mut registry = prelude::AnimalRegistry::new();
+    registry.register("owl", Box::new(prelude::Says("Hoot")));
+    assert_eq!(registry.speak("owl").as_deref(), Some("Hoot"));
+    prelude::child_fn();
+}
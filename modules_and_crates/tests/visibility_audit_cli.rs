@@ -0,0 +1,25 @@
+// runs the visibility_audit binary on this package's own source files
+use std::process::Command;
+
+fn audit(root: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_visibility_audit"))
+        .arg(root)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn finds_the_private_examples() {
+    let main = audit("src/main.rs");
+    assert!(main.contains("  crate::module_in_main::this_module_fn_is_in_main (src/main.rs:"));
+
+    let lib = audit("src/lib.rs");
+    assert!(lib.contains("  crate::parent::parent_fn (src/lib.rs:"));
+    // both file layouts are followed: garden.rs + garden/vegetable.rs and war/mod.rs + war/battleship/mod.rs
+    assert!(lib.contains("crate::garden::vegetable::Banana"));
+    assert!(lib.contains("crate::war::battleship::board::Board"));
+    assert!(!lib.contains("warning"));
+}
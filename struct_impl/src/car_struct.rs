@@ -1,12 +1,15 @@
+use crate::serialization::{Deserialize, Error, Record, Serialize, Value};
+
 // the "pub" before the struct makes the struct public, thus allowing any file to use it
 // the "pub" before the fields make them public, thus allowing any file to store value
 // in these fields
 // with "derive" attribute we can use pre-defined traits
-#[derive(Debug)] // this is required to print the struct, i.e. use the output formatter "Debug"
+#[derive(Debug, Clone, PartialEq)] // Debug is required to print the struct, i.e. use the output formatter "Debug"
 pub struct CarInfo{
     pub country_code: char, // if there is no pub -> field is private and cannot be modified
     pub model_num: u8,
     pub mileage: u8,
+    pub is_electric: bool, // added in schema version 2, see the Serialize impl below
 }
 
 // implementing fn for CarInfo. "impl" is used to implement fn for a struct
@@ -23,7 +26,8 @@ impl CarInfo{
         Self { 
             country_code, 
             model_num,
-            mileage: 10
+            mileage: 10,
+            is_electric: false,
         }
         // "country_code: country_code" or just "country_code"
         // both are correct as the "parameters" and "field" names are same
@@ -43,4 +47,77 @@ impl CarInfo{
         self.mileage += 5
     }
 
-}
\ No newline at end of file
+}
+
+// ------- Saving and Loading CarInfo -------------
+// version 1 -> country_code, model_num, mileage
+// version 2 -> added is_electric, files written with version 1 read it as false
+const CAR_INFO_VERSION: u32 = 2;
+
+impl Serialize for CarInfo {
+    fn to_value(&self) -> Value {
+        Value::Record(
+            Record::new(CAR_INFO_VERSION)
+                .with("country_code", &self.country_code)
+                .with("model_num", &self.model_num)
+                .with("mileage", &self.mileage)
+                .with("is_electric", &self.is_electric),
+        )
+    }
+}
+
+impl Deserialize for CarInfo {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        let record = value.as_record()?;
+        record.check_version(CAR_INFO_VERSION)?;
+        Ok(CarInfo {
+            country_code: record.field("country_code")?,
+            model_num: record.field("model_num")?,
+            mileage: record.field("mileage")?,
+            is_electric: record.field_since("is_electric", 2, false)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialization::{binary, from_binary, from_json, to_binary, to_json};
+
+    #[test]
+    fn round_trips_in_both_encodings() {
+        let mut car = CarInfo::new('I', 90);
+        car.is_electric = true;
+        assert_eq!(from_binary::<CarInfo>(&to_binary(&car)), Ok(car.clone()));
+        assert_eq!(from_json::<CarInfo>(&to_json(&car)), Ok(car));
+    }
+
+    #[test]
+    fn reads_version_1_files_with_default() {
+        // a file saved before is_electric existed
+        let old_json = r#"{"$version":1,"country_code":"U","model_num":54,"mileage":10}"#;
+        let car: CarInfo = from_json(old_json).unwrap();
+        assert_eq!(car, CarInfo { country_code: 'U', model_num: 54, mileage: 10, is_electric: false });
+
+        let old_binary = binary::encode(&Value::Record(
+            Record::new(1)
+                .with("country_code", &'U')
+                .with("model_num", &54u8)
+                .with("mileage", &10u8),
+        ));
+        assert_eq!(from_binary::<CarInfo>(&old_binary), Ok(car));
+    }
+
+    #[test]
+    fn rejects_newer_versions_and_bad_fields() {
+        let newer = r#"{"$version":3,"country_code":"U","model_num":54,"mileage":10,"is_electric":true}"#;
+        assert_eq!(
+            from_json::<CarInfo>(newer),
+            Err(Error::UnsupportedVersion { found: 3, supported: 2 })
+        );
+        let too_big = r#"{"$version":2,"country_code":"U","model_num":540,"mileage":10,"is_electric":true}"#;
+        assert_eq!(from_json::<CarInfo>(too_big), Err(Error::OutOfRange));
+        let missing = r#"{"$version":2,"country_code":"U","model_num":54,"mileage":10}"#;
+        assert_eq!(from_json::<CarInfo>(missing), Err(Error::MissingField("is_electric".to_string())));
+    }
+}
@@ -0,0 +1,441 @@
+// ------- Color Tuple Struct -------------
+// Color is a tuple struct, its fields have no names and are accessed with "." and the index
+// eg: color.0 -> red, color.1 -> green, color.2 -> blue, color.3 -> alpha (255 is fully opaque)
+use crate::serialization::{Deserialize, Error, Record, Serialize, Value};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8, pub u8);
+
+// HSL -> hue in degrees [0, 360), saturation and lightness in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+// HSV -> hue in degrees [0, 360), saturation and value in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f64,
+    pub s: f64,
+    pub v: f64,
+}
+
+// the reasons a string could not be turned into a Color
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseColorError {
+    // hex colors must have 3, 4, 6 or 8 digits after the "#"
+    InvalidLength(usize),
+    // a character after the "#" that is not 0-9, a-f or A-F
+    InvalidDigit(char),
+    // not a hex color and not one of the names in NAMED_COLORS
+    UnknownName(String),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseColorError::InvalidLength(len) => {
+                write!(f, "hex color must have 3, 4, 6 or 8 digits, found {}", len)
+            }
+            ParseColorError::InvalidDigit(c) => write!(f, "invalid hex digit {:?}", c),
+            ParseColorError::UnknownName(name) => write!(f, "unknown color name {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+// the 16 basic CSS colors plus a few common extras, names are lowercase
+pub const NAMED_COLORS: &[(&str, Color)] = &[
+    ("black", Color::rgb(0, 0, 0)),
+    ("silver", Color::rgb(192, 192, 192)),
+    ("gray", Color::rgb(128, 128, 128)),
+    ("white", Color::rgb(255, 255, 255)),
+    ("maroon", Color::rgb(128, 0, 0)),
+    ("red", Color::rgb(255, 0, 0)),
+    ("purple", Color::rgb(128, 0, 128)),
+    ("fuchsia", Color::rgb(255, 0, 255)),
+    ("green", Color::rgb(0, 128, 0)),
+    ("lime", Color::rgb(0, 255, 0)),
+    ("olive", Color::rgb(128, 128, 0)),
+    ("yellow", Color::rgb(255, 255, 0)),
+    ("navy", Color::rgb(0, 0, 128)),
+    ("blue", Color::rgb(0, 0, 255)),
+    ("teal", Color::rgb(0, 128, 128)),
+    ("aqua", Color::rgb(0, 255, 255)),
+    ("orange", Color::rgb(255, 165, 0)),
+    ("pink", Color::rgb(255, 192, 203)),
+    ("brown", Color::rgb(165, 42, 42)),
+    ("transparent", Color(0, 0, 0, 0)),
+];
+
+impl Color {
+    // "const fn" can be called when defining constants, like in NAMED_COLORS above
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color(r, g, b, a)
+    }
+
+    pub fn r(&self) -> u8 {
+        self.0
+    }
+
+    pub fn g(&self) -> u8 {
+        self.1
+    }
+
+    pub fn b(&self) -> u8 {
+        self.2
+    }
+
+    pub fn a(&self) -> u8 {
+        self.3
+    }
+
+    // returns a copy of the color with a different alpha
+    pub fn with_alpha(&self, a: u8) -> Self {
+        Color(self.0, self.1, self.2, a)
+    }
+
+    // ------- Hex -------------
+    // accepts "#rgb", "#rgba", "#rrggbb" and "#rrggbbaa", "#rgb" is short for "#rrggbb"
+    pub fn from_hex(hex: &str) -> Result<Self, ParseColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ParseColorError::InvalidDigit(c));
+        }
+        // every char is an ascii hex digit now, so each one is a single byte
+        let nibbles: Vec<u8> = digits
+            .chars()
+            .map(|c| c.to_digit(16).unwrap() as u8)
+            .collect();
+
+        match nibbles.len() {
+            // each short digit is repeated, eg: "f" -> "ff"
+            3 | 4 => {
+                let mut channels = [255u8; 4];
+                for (channel, nibble) in channels.iter_mut().zip(&nibbles) {
+                    *channel = nibble * 17;
+                }
+                Ok(Color(channels[0], channels[1], channels[2], channels[3]))
+            }
+            6 | 8 => {
+                let mut channels = [255u8; 4];
+                for (channel, pair) in channels.iter_mut().zip(nibbles.chunks(2)) {
+                    *channel = pair[0] * 16 + pair[1];
+                }
+                Ok(Color(channels[0], channels[1], channels[2], channels[3]))
+            }
+            len => Err(ParseColorError::InvalidLength(len)),
+        }
+    }
+
+    // "#rrggbb" for opaque colors, "#rrggbbaa" otherwise
+    pub fn to_hex(&self) -> String {
+        if self.3 == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.0, self.1, self.2, self.3)
+        }
+    }
+
+    // ------- Named Colors -------------
+    // the lookup ignores case, so "Red" and "RED" both work
+    pub fn named(name: &str) -> Option<Self> {
+        NAMED_COLORS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    // the reverse lookup, returns None if the color has no name
+    pub fn name(&self) -> Option<&'static str> {
+        NAMED_COLORS
+            .iter()
+            .find(|(_, color)| color == self)
+            .map(|(name, _)| *name)
+    }
+
+    // ------- HSL and HSV -------------
+    // the alpha channel is not part of HSL/HSV, from_hsl()/from_hsv() give opaque colors
+    pub fn to_hsl(&self) -> Hsl {
+        let (max, min, h) = self.hue_and_range();
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let s = if d == 0.0 {
+            0.0
+        } else {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsl { h, s, l }
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let c = (1.0 - (2.0 * hsl.l - 1.0).abs()) * hsl.s;
+        let m = hsl.l - c / 2.0;
+        Self::from_chroma(hsl.h, c, m)
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let (max, min, h) = self.hue_and_range();
+        let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+        Hsv { h, s, v: max }
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Self {
+        let c = hsv.v * hsv.s;
+        let m = hsv.v - c;
+        Self::from_chroma(hsv.h, c, m)
+    }
+
+    // max and min of the channels in [0, 1] and the hue in degrees, shared by HSL and HSV
+    fn hue_and_range(&self) -> (f64, f64, f64) {
+        let r = self.0 as f64 / 255.0;
+        let g = self.1 as f64 / 255.0;
+        let b = self.2 as f64 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let d = max - min;
+
+        let h = if d == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+        (max, min, h)
+    }
+
+    // builds a color from hue, chroma and the amount "m" added to every channel
+    fn from_chroma(h: f64, c: f64, m: f64) -> Self {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u8 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        Color::rgb(to_channel(r + m), to_channel(g + m), to_channel(b + m))
+    }
+
+    // ------- Blending -------------
+    // linear mix of every channel, t = 0.0 gives self and t = 1.0 gives other
+    pub fn mix(&self, other: &Color, t: f64) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color(
+            lerp(self.0, other.0),
+            lerp(self.1, other.1),
+            lerp(self.2, other.2),
+            lerp(self.3, other.3),
+        )
+    }
+
+    // paints self on top of the background using the alpha of both ("source over")
+    pub fn over(&self, background: &Color) -> Self {
+        let src_a = self.3 as f64 / 255.0;
+        let dst_a = background.3 as f64 / 255.0;
+        let out_a = src_a + dst_a * (1.0 - src_a);
+        if out_a == 0.0 {
+            return Color(0, 0, 0, 0);
+        }
+        let blend = |s: u8, d: u8| {
+            let s = s as f64 / 255.0;
+            let d = d as f64 / 255.0;
+            to_channel((s * src_a + d * dst_a * (1.0 - src_a)) / out_a)
+        };
+        Color(
+            blend(self.0, background.0),
+            blend(self.1, background.1),
+            blend(self.2, background.2),
+            to_channel(out_a),
+        )
+    }
+
+    // ------- Contrast (WCAG 2.x) -------------
+    // relative luminance, 0.0 for black and 1.0 for white, alpha is ignored
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.0) + 0.7152 * linear(self.1) + 0.0722 * linear(self.2)
+    }
+
+    // ranges from 1.0 (same luminance) to 21.0 (black on white), the order doesn't matter
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    // WCAG AA needs 4.5:1 for normal text and 3:1 for large text
+    pub fn meets_wcag_aa(&self, other: &Color, large_text: bool) -> bool {
+        let needed = if large_text { 3.0 } else { 4.5 };
+        self.contrast_ratio(other) >= needed
+    }
+}
+
+// converts a channel in [0, 1] to [0, 255]
+fn to_channel(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+// Display prints the hex form, so println!("{}", color) gives "#ff0000"
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+// FromStr lets us write "#ff0000".parse::<Color>() or "red".parse::<Color>()
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('#') {
+            return Color::from_hex(s);
+        }
+        Color::named(s).ok_or_else(|| ParseColorError::UnknownName(s.to_string()))
+    }
+}
+
+// ------- Saving and Loading Color -------------
+const COLOR_VERSION: u32 = 1;
+
+impl Serialize for Color {
+    fn to_value(&self) -> Value {
+        Value::Record(
+            Record::new(COLOR_VERSION)
+                .with("r", &self.0)
+                .with("g", &self.1)
+                .with("b", &self.2)
+                .with("a", &self.3),
+        )
+    }
+}
+
+impl Deserialize for Color {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        let record = value.as_record()?;
+        record.check_version(COLOR_VERSION)?;
+        Ok(Color(
+            record.field("r")?,
+            record.field("g")?,
+            record.field("b")?,
+            record.field("a")?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a tiny xorshift generator, so the property tests are repeatable without extra crates
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next_color(&mut self) -> Color {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            let [r, g, b, a, ..] = self.0.to_le_bytes();
+            Color(r, g, b, a)
+        }
+    }
+
+    #[test]
+    fn parses_short_and_long_hex() {
+        assert_eq!("#fff".parse(), Ok(Color::rgb(255, 255, 255)));
+        assert_eq!("#0f08".parse(), Ok(Color(0, 255, 0, 136)));
+        assert_eq!("#1A2b3C".parse(), Ok(Color::rgb(0x1a, 0x2b, 0x3c)));
+        assert_eq!("#11223380".parse(), Ok(Color(0x11, 0x22, 0x33, 0x80)));
+        assert_eq!("#12345".parse::<Color>(), Err(ParseColorError::InvalidLength(5)));
+        assert_eq!("#12g".parse::<Color>(), Err(ParseColorError::InvalidDigit('g')));
+    }
+
+    #[test]
+    fn named_colors_work_both_ways() {
+        assert_eq!("Orange".parse(), Ok(Color::rgb(255, 165, 0)));
+        assert_eq!(Color::rgb(0, 0, 128).name(), Some("navy"));
+        assert_eq!(Color::rgb(1, 2, 3).name(), None);
+        assert!(matches!(
+            "blurple".parse::<Color>(),
+            Err(ParseColorError::UnknownName(_))
+        ));
+    }
+
+    #[test]
+    fn contrast_ratio_matches_wcag_examples() {
+        let black = Color::rgb(0, 0, 0);
+        let white = Color::rgb(255, 255, 255);
+        assert!((black.contrast_ratio(&white) - 21.0).abs() < 1e-9);
+        assert_eq!(white.contrast_ratio(&white), 1.0);
+        // #767676 is the lightest gray that passes AA on white
+        assert!(Color::rgb(0x76, 0x76, 0x76).meets_wcag_aa(&white, false));
+        assert!(!Color::rgb(0x77, 0x77, 0x77).meets_wcag_aa(&white, false));
+    }
+
+    #[test]
+    fn blending() {
+        let red = Color::rgb(255, 0, 0);
+        let blue = Color::rgb(0, 0, 255);
+        assert_eq!(red.mix(&blue, 0.5), Color::rgb(128, 0, 128));
+        assert_eq!(red.mix(&blue, 0.0), red);
+        // half transparent red over opaque blue
+        assert_eq!(red.with_alpha(128).over(&blue), Color::rgb(128, 0, 127));
+        assert_eq!(Color(0, 0, 0, 0).over(&blue), blue);
+    }
+
+    #[test]
+    fn hsl_and_hsv_of_known_colors() {
+        let hsl = Color::rgb(255, 0, 0).to_hsl();
+        assert_eq!((hsl.h, hsl.s, hsl.l), (0.0, 1.0, 0.5));
+        let hsv = Color::rgb(0, 128, 0).to_hsv();
+        assert_eq!(hsv.h, 120.0);
+        assert_eq!(Color::from_hsv(Hsv { h: 240.0, s: 1.0, v: 1.0 }), Color::rgb(0, 0, 255));
+    }
+
+    #[test]
+    fn property_round_trips() {
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..20_000 {
+            let color = rng.next_color();
+            let opaque = color.with_alpha(255);
+
+            assert_eq!(color.to_string().parse(), Ok(color));
+            assert_eq!(Color::from_hex(&color.to_hex()), Ok(color));
+            assert_eq!(Color::from_hsl(color.to_hsl()), opaque);
+            assert_eq!(Color::from_hsv(color.to_hsv()), opaque);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_binary_and_json() {
+        use crate::serialization::{from_binary, from_json, to_binary, to_json};
+
+        let color = Color(12, 34, 56, 78);
+        assert_eq!(from_binary(&to_binary(&color)), Ok(color));
+        assert_eq!(from_json(&to_json(&color)), Ok(color));
+    }
+}
@@ -0,0 +1,158 @@
+// ComputerInfo lives in the library crate, so it can be used by main.rs and by other crates
+// the fields are "pub" so struct update syntax (..my_computer) keeps working outside this file
+use crate::serialization::{Deserialize, Error, Record, Serialize, Value};
+use crate::typestate::{Missing, Present};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputerInfo { // best practice is to order fields in alphabetical order
+    pub cost: f32,
+    pub id: i32,
+    pub is_64bit: bool,
+}
+
+impl ComputerInfo {
+    // starts a builder where both "id" and "cost" are still missing
+    // is_64bit is optional and defaults to true
+    pub fn builder() -> ComputerInfoBuilder<Missing, Missing> {
+        ComputerInfoBuilder {
+            cost: Missing,
+            id: Missing,
+            is_64bit: true,
+        }
+    }
+
+    // ------- with_* fns -------
+    // each fn takes &self, so the source is only borrowed and never partially moved
+    // all fields of ComputerInfo are Copy, so the remaining fields are simply copied over
+    pub fn with_cost(&self, cost: f32) -> Self {
+        Self { cost, ..self.clone() }
+    }
+
+    pub fn with_id(&self, id: i32) -> Self {
+        Self { id, ..self.clone() }
+    }
+
+    pub fn with_is_64bit(&self, is_64bit: bool) -> Self {
+        Self { is_64bit, ..self.clone() }
+    }
+}
+
+// C and I track whether "cost" and "id" were given, see typestate.rs
+/// Forgetting a required field fails to compile, build() is not defined
+/// for `ComputerInfoBuilder<Present<f32>, Missing>`:
+///
+/// ```compile_fail
+/// use struct_impl::computer::ComputerInfo;
+/// let computer = ComputerInfo::builder().cost(500.0).build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ComputerInfoBuilder<C, I> {
+    cost: C,
+    id: I,
+    is_64bit: bool,
+}
+
+// cost() can only be called while cost is Missing, calling it twice does not compile
+impl<I> ComputerInfoBuilder<Missing, I> {
+    pub fn cost(self, cost: f32) -> ComputerInfoBuilder<Present<f32>, I> {
+        ComputerInfoBuilder {
+            cost: Present(cost),
+            id: self.id,
+            is_64bit: self.is_64bit,
+        }
+    }
+}
+
+impl<C> ComputerInfoBuilder<C, Missing> {
+    pub fn id(self, id: i32) -> ComputerInfoBuilder<C, Present<i32>> {
+        ComputerInfoBuilder {
+            cost: self.cost,
+            id: Present(id),
+            is_64bit: self.is_64bit,
+        }
+    }
+}
+
+// optional fields can be set in any state
+impl<C, I> ComputerInfoBuilder<C, I> {
+    pub fn is_64bit(mut self, is_64bit: bool) -> Self {
+        self.is_64bit = is_64bit;
+        self
+    }
+}
+
+// build() only exists once both required fields are Present
+impl ComputerInfoBuilder<Present<f32>, Present<i32>> {
+    pub fn build(self) -> ComputerInfo {
+        ComputerInfo {
+            cost: self.cost.into_inner(),
+            id: self.id.into_inner(),
+            is_64bit: self.is_64bit,
+        }
+    }
+}
+
+// ------- Saving and Loading ComputerInfo -------------
+const COMPUTER_INFO_VERSION: u32 = 1;
+
+impl Serialize for ComputerInfo {
+    fn to_value(&self) -> Value {
+        Value::Record(
+            Record::new(COMPUTER_INFO_VERSION)
+                .with("cost", &self.cost)
+                .with("id", &self.id)
+                .with("is_64bit", &self.is_64bit),
+        )
+    }
+}
+
+impl Deserialize for ComputerInfo {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        let record = value.as_record()?;
+        record.check_version(COMPUTER_INFO_VERSION)?;
+        Ok(ComputerInfo {
+            cost: record.field("cost")?,
+            id: record.field("id")?,
+            is_64bit: record.field("is_64bit")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_sets_required_and_default_fields() {
+        // required fields can be given in any order
+        let computer = ComputerInfo::builder().id(25).cost(500.0).build();
+        assert_eq!(
+            computer,
+            ComputerInfo { cost: 500.0, id: 25, is_64bit: true }
+        );
+
+        let computer = ComputerInfo::builder().is_64bit(false).cost(120.0).id(7).build();
+        assert!(!computer.is_64bit);
+    }
+
+    #[test]
+    fn with_fns_leave_the_source_untouched() {
+        let my_computer = ComputerInfo::builder().cost(500.0).id(14).build();
+        let my_computer_2 = my_computer.with_id(100);
+
+        assert_eq!(my_computer.id, 14);
+        assert_eq!(my_computer_2.id, 100);
+        assert_eq!(my_computer_2.cost, my_computer.cost);
+        assert!(!my_computer.with_is_64bit(false).is_64bit);
+        assert_eq!(my_computer.with_cost(1.5).cost, 1.5);
+    }
+
+    #[test]
+    fn round_trips_through_binary_and_json() {
+        use crate::serialization::{from_binary, from_json, to_binary, to_json};
+
+        let computer = ComputerInfo::builder().cost(499.99).id(-3).is_64bit(false).build();
+        assert_eq!(from_binary(&to_binary(&computer)), Ok(computer.clone()));
+        assert_eq!(from_json(&to_json(&computer)), Ok(computer));
+    }
+}
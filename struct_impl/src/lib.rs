@@ -0,0 +1,16 @@
+// The structs that main.rs used to declare itself now live in the library crate,
+// one module each, so they can have their own tests and be used from other crates too.
+// Every struct and its fields are "pub", main.rs reaches them with paths like struct_impl::user::User
+
+pub mod car_struct;
+pub mod color;
+pub mod computer;
+pub mod serialization;
+pub mod typestate;
+pub mod user;
+
+// re-exporting the structs, so they can be used as struct_impl::User instead of struct_impl::user::User
+pub use car_struct::CarInfo;
+pub use color::Color;
+pub use computer::ComputerInfo;
+pub use user::User;
@@ -10,33 +10,28 @@ eg: snake_case -> Snake Case, all small letters, words separated by _
     PascalCase -> first letter of every word is Capital and no space between words
 */
 
-// mod will let us use the struct in car_struct.rs
-mod car_struct;          // mod is short for module, here car_struct.rs is a module
-use car_struct::CarInfo; // will let us use the struct CarInfo from car_struct.rs
-
-#[allow(dead_code)] // supress unused structs, only supresses in ComputerInfo Struct
-#[allow(unused_variables)] // this supresses warnings only in ComputerInfo struct
-struct ComputerInfo{   // best practice is to order fields in alphabetical order  
-    cost: f32,
-    id: i32, 
-    is_64bit: bool,
-}
-
-#[allow(dead_code)] // this supresses warnings only in User struct
-struct User{
-    name: String,
-    age: u8,
-    job: String,
-}
-
-// creating a struct tuple
-#[allow(dead_code)] 
-struct Color(u8, u8, u8);
+// car_struct.rs is a module of the library crate, declared with "pub mod car_struct;" in lib.rs
+// mod is short for module, and "use" will let us use the struct CarInfo from car_struct.rs
+use struct_impl::car_struct::CarInfo;
+use struct_impl::serialization::{from_json, to_binary, to_json};
+
+// ComputerInfo and User are defined in the library crate (src/lib.rs), so other crates can use them too
+// here struct_impl is the name of our package, which is also the name of the library crate
+use struct_impl::{ComputerInfo, User};
+
+// Color is a struct tuple defined in src/color.rs -> struct Color(u8, u8, u8, u8)
+use struct_impl::Color;
 
 #[allow(unused_variables)] // as its placed above main fn, it supress warnings in main fn
 fn main(){
 
-    let rgb = Color(255,255,255); // each element can be accesed with "."
+    let rgb = Color(255,255,255,255); // each element can be accesed with "."
+    println!("Red channel: {}, as hex: {}",rgb.0,rgb); // Display prints the color as hex
+
+    // FromStr lets us parse hex codes and color names into a Color
+    let orange: Color = "orange".parse().unwrap();
+    let navy: Color = "#000080".parse().unwrap();
+    println!("Contrast of {} on {}: {:.2}\n",orange,navy,orange.contrast_ratio(&navy));
 
     let my_computer = ComputerInfo{
         cost: 500.0,
@@ -96,6 +91,20 @@ fn main(){
     // user_1.job becomes invalid(deallocated) but user_1.name, user_1.age will still work
     // println!("{:?}",user_1.job);
 
+    // ------- Avoiding the Partial Move -------------
+    // with_*() fns only borrow the source and clone the fields they don't replace
+    // so user_4 gets a copy of the job and user_4 stays whole afterwards
+    let user_4 = User::builder().name("Zoro").age(21).job("Swordsman").build();
+    let user_5 = user_4.with_name("Sanji").with_job("Cook");
+    println!("{} the {} and {} the {}", user_4.name, user_4.job, user_5.name, user_5.job);
+
+    // ------- Builder Pattern -------------
+    // the builder only has a build() fn once the required fields (cost and id) are given
+    // ComputerInfo::builder().cost(500.0).build(); -> fails to compile as "id" is missing
+    let my_computer_3 = ComputerInfo::builder().cost(750.0).id(3).is_64bit(false).build();
+    let my_computer_4 = my_computer_3.with_id(4); // my_computer_3 is still usable
+    println!("Computer {} costs {}", my_computer_3.id, my_computer_4.cost);
+
     //-----------------------------------------------------------------------------
     println!("--------------------------------------------------------------------");
     // --- Using Struct From Other Files ---
@@ -103,6 +112,7 @@ fn main(){
         country_code: 'U',
         model_num: 54,
         mileage: 10,
+        is_electric: false,
     };
 
     // using CarInfo new() fn to create new variable
@@ -124,4 +134,12 @@ fn main(){
     //Note: dbg! macro prints to stderr while println! to stdout
     //Note: dbg! takes the ownership of passed var, while println! takes reference
 
+    // ----- Saving and Loading Structs ------
+    // to_json() and to_binary() use the Serialize trait, from_json() the Deserialize trait
+    let saved = to_json(&new_car);
+    println!("\nCar saved as JSON: {}",saved);
+    let loaded: CarInfo = from_json(&saved).unwrap();
+    println!("Loaded car is the same: {}",loaded == new_car);
+    println!("Car saved as binary takes {} bytes",to_binary(&new_car).len());
+
 }
\ No newline at end of file
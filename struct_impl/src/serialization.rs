@@ -0,0 +1,250 @@
+// ------- Serialization -------------
+// Saving and loading our structs without any external crate.
+// A type turns itself into a "Value" (Serialize) and builds itself back from one (Deserialize),
+// the submodules then turn a Value into bytes:
+// binary.rs -> compact tagged binary encoding
+// json.rs   -> human readable JSON
+// so every struct is hand written once and works with both encodings.
+use std::fmt;
+
+pub mod binary;
+pub mod json;
+
+// how many records can be nested inside each other when decoding,
+// the decoders are recursive so deeper input would overflow the stack
+pub const MAX_DEPTH: usize = 64;
+
+// a format independent tree of data, the middle step between a struct and its encoding
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Char(char),
+    Str(String),
+    Record(Record),
+}
+
+// a struct turned into named fields, "version" is the schema version it was written with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub version: u32,
+    pub fields: Vec<(String, Value)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    // the input ended in the middle of a value
+    UnexpectedEof,
+    // a binary type tag that is not one of the tags in binary.rs
+    UnknownTag(u8),
+    // a string that is not valid UTF-8 or a char that is not a valid unicode scalar
+    InvalidText,
+    // bytes or characters left over after the value was read
+    TrailingData,
+    // malformed JSON, "position" is the byte offset where parsing failed
+    Json { position: usize, message: String },
+    MissingField(String),
+    // the Value has a different type than the field expects, eg: Str for a u8 field
+    TypeMismatch { expected: &'static str },
+    // the number does not fit the field, eg: 300 for a u8 field
+    OutOfRange,
+    // the data was written by a newer version of the struct than this code knows about
+    UnsupportedVersion { found: u32, supported: u32 },
+    // records nested deeper than MAX_DEPTH
+    TooDeep,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnexpectedEof => write!(f, "unexpected end of input"),
+            Error::UnknownTag(tag) => write!(f, "unknown type tag {:#04x}", tag),
+            Error::InvalidText => write!(f, "invalid utf-8 text or char"),
+            Error::TrailingData => write!(f, "trailing data after value"),
+            Error::Json { position, message } => {
+                write!(f, "invalid json at byte {}: {}", position, message)
+            }
+            Error::MissingField(name) => write!(f, "missing field {:?}", name),
+            Error::TypeMismatch { expected } => write!(f, "expected a value of type {}", expected),
+            Error::OutOfRange => write!(f, "number out of range"),
+            Error::UnsupportedVersion { found, supported } => write!(
+                f,
+                "schema version {} is newer than the supported version {}",
+                found, supported
+            ),
+            Error::TooDeep => write!(f, "records are nested deeper than {}", MAX_DEPTH),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// ------- The Trait Pair -------------
+pub trait Serialize {
+    fn to_value(&self) -> Value;
+}
+
+pub trait Deserialize: Sized {
+    fn from_value(value: &Value) -> Result<Self, Error>;
+}
+
+// ------- Encoding Helpers -------------
+pub fn to_binary<T: Serialize>(item: &T) -> Vec<u8> {
+    binary::encode(&item.to_value())
+}
+
+pub fn from_binary<T: Deserialize>(bytes: &[u8]) -> Result<T, Error> {
+    T::from_value(&binary::decode(bytes)?)
+}
+
+pub fn to_json<T: Serialize>(item: &T) -> String {
+    json::encode(&item.to_value())
+}
+
+pub fn from_json<T: Deserialize>(text: &str) -> Result<T, Error> {
+    T::from_value(&json::decode(text)?)
+}
+
+impl Record {
+    pub fn new(version: u32) -> Self {
+        Record { version, fields: Vec::new() }
+    }
+
+    // adds a field, returns self so calls can be chained
+    pub fn with<T: Serialize>(mut self, name: &str, value: &T) -> Self {
+        self.fields.push((name.to_string(), value.to_value()));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.fields.iter().find(|(field, _)| field == name).map(|(_, value)| value)
+    }
+
+    // reads a required field
+    pub fn field<T: Deserialize>(&self, name: &str) -> Result<T, Error> {
+        match self.get(name) {
+            Some(value) => T::from_value(value),
+            None => Err(Error::MissingField(name.to_string())),
+        }
+    }
+
+    // reads a field that was added in schema version "since",
+    // data written before that version gets the default instead
+    pub fn field_since<T: Deserialize>(&self, name: &str, since: u32, default: T) -> Result<T, Error> {
+        if self.version < since {
+            return Ok(default);
+        }
+        self.field(name)
+    }
+
+    // fails when the data is newer than the code reading it
+    pub fn check_version(&self, supported: u32) -> Result<(), Error> {
+        if self.version > supported {
+            return Err(Error::UnsupportedVersion { found: self.version, supported });
+        }
+        Ok(())
+    }
+}
+
+impl Value {
+    pub fn as_record(&self) -> Result<&Record, Error> {
+        match self {
+            Value::Record(record) => Ok(record),
+            _ => Err(Error::TypeMismatch { expected: "record" }),
+        }
+    }
+}
+
+// ------- Primitive Implementations -------------
+impl Serialize for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl Deserialize for bool {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(Error::TypeMismatch { expected: "bool" }),
+        }
+    }
+}
+
+// integers are all stored as i64 and checked when read back, so u8 and i32 share the code
+macro_rules! impl_integer {
+    ($($int:ty),*) => {$(
+        impl Serialize for $int {
+            fn to_value(&self) -> Value {
+                Value::Int(*self as i64)
+            }
+        }
+
+        impl Deserialize for $int {
+            fn from_value(value: &Value) -> Result<Self, Error> {
+                match value {
+                    Value::Int(i) => <$int>::try_from(*i).map_err(|_| Error::OutOfRange),
+                    _ => Err(Error::TypeMismatch { expected: stringify!($int) }),
+                }
+            }
+        }
+    )*};
+}
+
+impl_integer!(u8, i32);
+
+impl Serialize for f32 {
+    fn to_value(&self) -> Value {
+        Value::Float(*self as f64)
+    }
+}
+
+impl Deserialize for f32 {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::Float(f) => Ok(*f as f32),
+            // JSON writes whole numbers without a decimal point, eg: 500
+            Value::Int(i) => Ok(*i as f32),
+            _ => Err(Error::TypeMismatch { expected: "f32" }),
+        }
+    }
+}
+
+impl Serialize for char {
+    fn to_value(&self) -> Value {
+        Value::Char(*self)
+    }
+}
+
+impl Deserialize for char {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::Char(c) => Ok(*c),
+            // JSON has no char type, so a char comes back as a one character string
+            Value::Str(s) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(Error::TypeMismatch { expected: "char" }),
+                }
+            }
+            _ => Err(Error::TypeMismatch { expected: "char" }),
+        }
+    }
+}
+
+impl Serialize for String {
+    fn to_value(&self) -> Value {
+        Value::Str(self.clone())
+    }
+}
+
+impl Deserialize for String {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::Str(s) => Ok(s.clone()),
+            _ => Err(Error::TypeMismatch { expected: "string" }),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+// ------- Compact Binary Encoding -------------
+// every value starts with a one byte tag telling its type, followed by its data
+// integers and lengths use a varint (7 bits per byte, the high bit says "more bytes follow"),
+// so small numbers like a u8 mileage only take a single byte
+use super::{Error, Record, Value, MAX_DEPTH};
+
+const TAG_FALSE: u8 = 0x00;
+const TAG_TRUE: u8 = 0x01;
+const TAG_INT: u8 = 0x02;
+const TAG_FLOAT: u8 = 0x03;
+const TAG_CHAR: u8 = 0x04;
+const TAG_STR: u8 = 0x05;
+// record -> version, number of fields, then (name, value) for each field
+const TAG_RECORD: u8 = 0x06;
+
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_value(&mut out, value);
+    out
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Value, Error> {
+    let mut reader = Reader { bytes, pos: 0, depth: 0 };
+    let value = reader.value()?;
+    if reader.pos != bytes.len() {
+        return Err(Error::TrailingData);
+    }
+    Ok(value)
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Bool(false) => out.push(TAG_FALSE),
+        Value::Bool(true) => out.push(TAG_TRUE),
+        Value::Int(i) => {
+            out.push(TAG_INT);
+            // zigzag maps small negative numbers to small positive ones: 0, -1, 1, -2 -> 0, 1, 2, 3
+            write_varint(out, ((i << 1) ^ (i >> 63)) as u64);
+        }
+        Value::Float(f) => {
+            out.push(TAG_FLOAT);
+            out.extend_from_slice(&f.to_le_bytes());
+        }
+        Value::Char(c) => {
+            out.push(TAG_CHAR);
+            write_varint(out, *c as u64);
+        }
+        Value::Str(s) => {
+            out.push(TAG_STR);
+            write_str(out, s);
+        }
+        Value::Record(record) => {
+            out.push(TAG_RECORD);
+            write_varint(out, record.version as u64);
+            write_varint(out, record.fields.len() as u64);
+            for (name, field) in &record.fields {
+                write_str(out, name);
+                write_value(out, field);
+            }
+        }
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_varint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+// keeps track of how far into the bytes we have read, and how many records deep
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self.pos.checked_add(len).ok_or(Error::UnexpectedEof)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(Error::UnexpectedEof)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(Error::OutOfRange)
+    }
+
+    fn length(&mut self) -> Result<usize, Error> {
+        usize::try_from(self.varint()?).map_err(|_| Error::OutOfRange)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let len = self.length()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::InvalidText)
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        match self.byte()? {
+            TAG_FALSE => Ok(Value::Bool(false)),
+            TAG_TRUE => Ok(Value::Bool(true)),
+            TAG_INT => {
+                let n = self.varint()?;
+                Ok(Value::Int(((n >> 1) as i64) ^ -((n & 1) as i64)))
+            }
+            TAG_FLOAT => {
+                let mut raw = [0u8; 8];
+                raw.copy_from_slice(self.take(8)?);
+                Ok(Value::Float(f64::from_le_bytes(raw)))
+            }
+            TAG_CHAR => {
+                let n = u32::try_from(self.varint()?).map_err(|_| Error::InvalidText)?;
+                char::from_u32(n).map(Value::Char).ok_or(Error::InvalidText)
+            }
+            TAG_STR => Ok(Value::Str(self.string()?)),
+            TAG_RECORD => {
+                if self.depth == MAX_DEPTH {
+                    return Err(Error::TooDeep);
+                }
+                self.depth += 1;
+                let version = u32::try_from(self.varint()?).map_err(|_| Error::OutOfRange)?;
+                let count = self.length()?;
+                // not using Vec::with_capacity(count), a broken count could ask for huge memory
+                let mut fields = Vec::new();
+                for _ in 0..count {
+                    let name = self.string()?;
+                    fields.push((name, self.value()?));
+                }
+                self.depth -= 1;
+                Ok(Value::Record(Record { version, fields }))
+            }
+            tag => Err(Error::UnknownTag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_value_type() {
+        let value = Value::Record(Record {
+            version: 3,
+            fields: vec![
+                ("yes".to_string(), Value::Bool(true)),
+                ("min".to_string(), Value::Int(i64::MIN)),
+                ("neg".to_string(), Value::Int(-1)),
+                ("pi".to_string(), Value::Float(3.5)),
+                ("c".to_string(), Value::Char('é')),
+                ("s".to_string(), Value::Str("héllo".to_string())),
+            ],
+        });
+        assert_eq!(decode(&encode(&value)), Ok(value));
+    }
+
+    #[test]
+    fn small_ints_take_two_bytes() {
+        assert_eq!(encode(&Value::Int(10)), vec![TAG_INT, 20]);
+        assert_eq!(encode(&Value::Int(-1)), vec![TAG_INT, 1]);
+    }
+
+    #[test]
+    fn rejects_broken_input() {
+        assert_eq!(decode(&[]), Err(Error::UnexpectedEof));
+        assert_eq!(decode(&[0x42]), Err(Error::UnknownTag(0x42)));
+        assert_eq!(decode(&[TAG_STR, 5, b'a']), Err(Error::UnexpectedEof));
+        assert_eq!(decode(&[TAG_TRUE, TAG_TRUE]), Err(Error::TrailingData));
+    }
+
+    // a record with one field "" holding the next record, the innermost one has no fields
+    fn nested(depth: usize) -> Vec<u8> {
+        let mut bytes = [TAG_RECORD, 1, 1, 0].repeat(depth - 1);
+        bytes.extend([TAG_RECORD, 1, 0]);
+        bytes
+    }
+
+    #[test]
+    fn limits_the_nesting() {
+        assert!(decode(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(decode(&nested(MAX_DEPTH + 1)), Err(Error::TooDeep));
+        // would overflow the stack without the limit
+        assert_eq!(decode(&nested(100_000)), Err(Error::TooDeep));
+    }
+}
@@ -0,0 +1,300 @@
+// ------- JSON Encoding -------------
+// a record becomes a JSON object, its schema version is stored under the "$version" key
+// eg: {"$version":2,"country_code":"U","model_num":54,"mileage":10,"is_electric":false}
+// JSON has no char type, so chars are written as one character strings
+use super::{Error, Record, Value, MAX_DEPTH};
+
+const VERSION_KEY: &str = "$version";
+
+pub fn encode(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+pub fn decode(text: &str) -> Result<Value, Error> {
+    let mut parser = Parser { text, pos: 0, depth: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != text.len() {
+        return Err(Error::TrailingData);
+    }
+    Ok(value)
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Int(i) => out.push_str(&i.to_string()),
+        // {:?} always keeps a ".0" or an exponent, so the number is read back as a float
+        // JSON cannot store NaN or infinity, they are written as null and fail to load
+        Value::Float(f) if f.is_finite() => out.push_str(&format!("{:?}", f)),
+        Value::Float(_) => out.push_str("null"),
+        Value::Char(c) => write_str(out, &c.to_string()),
+        Value::Str(s) => write_str(out, s),
+        Value::Record(record) => {
+            out.push('{');
+            write_str(out, VERSION_KEY);
+            out.push(':');
+            out.push_str(&record.version.to_string());
+            for (name, field) in &record.fields {
+                out.push(',');
+                write_str(out, name);
+                out.push(':');
+                write_value(out, field);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// a small recursive descent parser, only for the subset of JSON that Value can hold
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: usize, // how many records are open
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        Error::Json { position: self.pos, message: message.to_string() }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b) if b == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(self.error(&format!("expected '{}'", byte as char))),
+            None => Err(Error::UnexpectedEof),
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        if self.text[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            return true;
+        }
+        false
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(Error::UnexpectedEof),
+            Some(b'{') => self.record(),
+            Some(b'"') => Ok(Value::Str(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) if self.keyword("true") => Ok(Value::Bool(true)),
+            Some(_) if self.keyword("false") => Ok(Value::Bool(false)),
+            Some(_) if self.keyword("null") => Err(self.error("null is not supported")),
+            Some(_) => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn record(&mut self) -> Result<Value, Error> {
+        self.expect(b'{')?;
+        if self.depth == MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        self.depth += 1;
+        let mut version = None;
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                let name = self.string()?;
+                self.expect(b':')?;
+                let value = self.value()?;
+                if name == VERSION_KEY {
+                    match value {
+                        Value::Int(v) => version = Some(u32::try_from(v).map_err(|_| Error::OutOfRange)?),
+                        _ => return Err(Error::TypeMismatch { expected: "version number" }),
+                    }
+                } else {
+                    fields.push((name, value));
+                }
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(_) => return Err(self.error("expected ',' or '}'")),
+                    None => return Err(Error::UnexpectedEof),
+                }
+            }
+        }
+
+        let version = version.ok_or_else(|| Error::MissingField(VERSION_KEY.to_string()))?;
+        self.depth -= 1;
+        Ok(Value::Record(Record { version, fields }))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        loop {
+            let (offset, c) = chars.next().ok_or(Error::UnexpectedEof)?;
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let (_, escaped) = chars.next().ok_or(Error::UnexpectedEof)?;
+                    match escaped {
+                        '"' => out.push('"'),
+                        '\\' => out.push('\\'),
+                        '/' => out.push('/'),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let first = hex4(&mut chars)?;
+                            // characters outside the basic plane are written as two \u escapes
+                            let code = if (0xd800..0xdc00).contains(&first) {
+                                if chars.next().map(|(_, c)| c) != Some('\\')
+                                    || chars.next().map(|(_, c)| c) != Some('u')
+                                {
+                                    return Err(Error::InvalidText);
+                                }
+                                // the second one must be a low surrogate
+                                let second = hex4(&mut chars)?;
+                                if !(0xdc00..0xe000).contains(&second) {
+                                    return Err(Error::InvalidText);
+                                }
+                                0x10000 + ((first - 0xd800) << 10) + (second - 0xdc00)
+                            } else {
+                                first
+                            };
+                            out.push(char::from_u32(code).ok_or(Error::InvalidText)?);
+                        }
+                        _ => {
+                            self.pos += offset;
+                            return Err(self.error("invalid escape"));
+                        }
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        let literal = &self.text[start..self.pos];
+        if literal.contains(['.', 'e', 'E']) {
+            literal.parse().map(Value::Float).map_err(|_| self.error("invalid number"))
+        } else {
+            literal.parse().map(Value::Int).map_err(|_| self.error("invalid integer"))
+        }
+    }
+}
+
+// reads the 4 hex digits of a \u escape
+fn hex4(chars: &mut std::str::CharIndices) -> Result<u32, Error> {
+    let mut code = 0;
+    for _ in 0..4 {
+        let (_, c) = chars.next().ok_or(Error::UnexpectedEof)?;
+        code = code * 16 + c.to_digit(16).ok_or(Error::InvalidText)?;
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_nested_records() {
+        let value = Value::Record(Record {
+            version: 1,
+            fields: vec![
+                ("name".to_string(), Value::Str("tab\t \"quote\" \u{1}".to_string())),
+                ("inner".to_string(), Value::Record(Record { version: 4, fields: vec![] })),
+                ("cost".to_string(), Value::Float(-0.25)),
+                ("id".to_string(), Value::Int(-7)),
+            ],
+        });
+        assert_eq!(decode(&encode(&value)), Ok(value));
+    }
+
+    #[test]
+    fn reads_whitespace_and_escapes() {
+        let text = r#" { "$version" : 2 , "s" : "\u00e9\ud83d\ude00\/" , "ok" : true } "#;
+        let expected = Value::Record(Record {
+            version: 2,
+            fields: vec![
+                ("s".to_string(), Value::Str("é😀/".to_string())),
+                ("ok".to_string(), Value::Bool(true)),
+            ],
+        });
+        assert_eq!(decode(text), Ok(expected));
+    }
+
+    #[test]
+    fn rejects_broken_input() {
+        assert_eq!(decode(r#"{"a":1}"#), Err(Error::MissingField("$version".to_string())));
+        assert_eq!(decode(r#"{"$version":1,"a":1"#), Err(Error::UnexpectedEof));
+        assert_eq!(decode("true false"), Err(Error::TrailingData));
+        assert!(matches!(decode("[1]"), Err(Error::Json { position: 0, .. })));
+        // a high surrogate must be followed by a low one, and a low one can't come alone
+        assert_eq!(decode(r#""\ud800\u0041""#), Err(Error::InvalidText));
+        assert_eq!(decode(r#""\ud800\ud800""#), Err(Error::InvalidText));
+        assert_eq!(decode(r#""\udc00""#), Err(Error::InvalidText));
+    }
+
+    // a record with one field "" holding the next record, the innermost one has no fields
+    fn nested(depth: usize) -> String {
+        format!("{}{}{}", r#"{"$version":1,"":"#.repeat(depth - 1), r#"{"$version":1}"#, "}".repeat(depth - 1))
+    }
+
+    #[test]
+    fn limits_the_nesting() {
+        assert!(decode(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(decode(&nested(MAX_DEPTH + 1)), Err(Error::TooDeep));
+        // would overflow the stack without the limit
+        assert_eq!(decode(&nested(100_000)), Err(Error::TooDeep));
+    }
+}
@@ -0,0 +1,20 @@
+// ------- Typestate Markers -------------
+// A builder can use its "generic parameters" to remember which fields were already given.
+// Each required field starts as "Missing" and becomes "Present<T>" once its setter is called.
+// The build() fn is only implemented when every required field is "Present", so forgetting
+// a required field is a compile time error instead of a runtime panic.
+
+// the field has not been set yet, this is a zero sized type so it costs nothing at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Missing;
+
+// the field has been set and holds its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Present<T>(pub T);
+
+impl<T> Present<T> {
+    // gives back the value stored inside the marker
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
@@ -0,0 +1,159 @@
+// User lives in the library crate, so it can be used by main.rs and by other crates
+use crate::serialization::{Deserialize, Error, Record, Serialize, Value};
+use crate::typestate::{Missing, Present};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub name: String,
+    pub age: u8,
+    pub job: String,
+}
+
+impl User {
+    // starts a builder where "name" and "age" are still missing
+    // job is optional and defaults to an empty String
+    pub fn builder() -> UserBuilder<Missing, Missing> {
+        UserBuilder {
+            name: Missing,
+            age: Missing,
+            job: String::new(),
+        }
+    }
+
+    // ------- with_* fns -------
+    // "..user_1" moves the String fields out of user_1, making user_1.job unusable (see main.rs)
+    // these fns only borrow self and clone the fields that are not replaced,
+    // so the source User stays fully usable afterwards
+    pub fn with_name(&self, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            age: self.age,
+            job: self.job.clone(),
+        }
+    }
+
+    pub fn with_age(&self, age: u8) -> Self {
+        Self { age, ..self.clone() }
+    }
+
+    pub fn with_job(&self, job: impl Into<String>) -> Self {
+        Self {
+            name: self.name.clone(),
+            age: self.age,
+            job: job.into(),
+        }
+    }
+}
+
+// N and A track whether "name" and "age" were given, see typestate.rs
+/// Forgetting a required field fails to compile, build() is not defined
+/// for `UserBuilder<Present<String>, Missing>`:
+///
+/// ```compile_fail
+/// use struct_impl::user::User;
+/// let user = User::builder().name("Lucifer").job("Dev").build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct UserBuilder<N, A> {
+    name: N,
+    age: A,
+    job: String,
+}
+
+impl<A> UserBuilder<Missing, A> {
+    pub fn name(self, name: impl Into<String>) -> UserBuilder<Present<String>, A> {
+        UserBuilder {
+            name: Present(name.into()),
+            age: self.age,
+            job: self.job,
+        }
+    }
+}
+
+impl<N> UserBuilder<N, Missing> {
+    pub fn age(self, age: u8) -> UserBuilder<N, Present<u8>> {
+        UserBuilder {
+            name: self.name,
+            age: Present(age),
+            job: self.job,
+        }
+    }
+}
+
+impl<N, A> UserBuilder<N, A> {
+    pub fn job(mut self, job: impl Into<String>) -> Self {
+        self.job = job.into();
+        self
+    }
+}
+
+impl UserBuilder<Present<String>, Present<u8>> {
+    pub fn build(self) -> User {
+        User {
+            name: self.name.into_inner(),
+            age: self.age.into_inner(),
+            job: self.job,
+        }
+    }
+}
+
+// ------- Saving and Loading User -------------
+const USER_VERSION: u32 = 1;
+
+impl Serialize for User {
+    fn to_value(&self) -> Value {
+        Value::Record(
+            Record::new(USER_VERSION)
+                .with("name", &self.name)
+                .with("age", &self.age)
+                .with("job", &self.job),
+        )
+    }
+}
+
+impl Deserialize for User {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        let record = value.as_record()?;
+        record.check_version(USER_VERSION)?;
+        Ok(User {
+            name: record.field("name")?,
+            age: record.field("age")?,
+            job: record.field("job")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_defaults_job_to_empty() {
+        let user = User::builder().age(50).name("Lucifer").build();
+        assert_eq!(user.name, "Lucifer");
+        assert_eq!(user.age, 50);
+        assert_eq!(user.job, "");
+    }
+
+    #[test]
+    fn with_fns_never_partially_move_the_source() {
+        let user_1 = User::builder().name("Lucifer").age(50).job("Dev").build();
+        let user_2 = user_1.with_name("Luffy").with_job("Manager");
+        let user_3 = user_1.with_age(45);
+
+        // user_1 is still whole, including its job
+        assert_eq!(user_1.job, "Dev");
+        assert_eq!(user_2, User { name: "Luffy".to_string(), age: 50, job: "Manager".to_string() });
+        assert_eq!(user_3.job, user_1.job);
+        assert_eq!(user_3.age, 45);
+    }
+
+    #[test]
+    fn round_trips_through_binary_and_json() {
+        use crate::serialization::{from_binary, from_json, to_binary, to_json};
+
+        let user = User::builder().name("Luffy \"Straw Hat\"").age(19).job("Captain").build();
+        assert_eq!(from_binary(&to_binary(&user)), Ok(user.clone()));
+        assert_eq!(from_json(&to_json(&user)), Ok(user));
+    }
+}